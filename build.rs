@@ -1,4 +1,6 @@
+use std::env;
 use std::fs;
+use std::path::Path;
 
 macro_rules! readme {
     () => {"\
@@ -45,7 +47,40 @@ fn use_example(file: &str) -> std::io::Result<String> {
     fs::read_to_string(file)
 }
 
+// NOTE: Every .pile file in src/stdlib becomes a module of the standard
+// library, e.g. src/stdlib/math.pile can be used as "std/math".
+fn embed_stdlib() -> std::io::Result<()> {
+    let stdlib_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("src")
+        .join("stdlib");
+    println!("cargo:rerun-if-changed={}", stdlib_dir.to_string_lossy());
+
+    let mut modules: Vec<_> = fs::read_dir(&stdlib_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    modules.retain(|path| path.extension().unwrap_or_default() == "pile");
+    modules.sort();
+
+    let mut table = String::from("pub const MODULES: &[(&str, &str)] = &[\n");
+    for module in modules {
+        println!("cargo:rerun-if-changed={}", module.to_string_lossy());
+        table += &format!(
+            "    ({:?}, include_str!({:?})),\n",
+            module.file_stem().unwrap().to_string_lossy(),
+            module.to_string_lossy()
+        );
+    }
+    table += "];\n";
+
+    fs::write(
+        Path::new(&env::var("OUT_DIR").unwrap()).join("stdlib.rs"),
+        table,
+    )
+}
+
 fn main() -> std::io::Result<()> {
+    embed_stdlib()?;
+
     fs::write(
         "README.md",
         format!(
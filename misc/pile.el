@@ -20,33 +20,84 @@
   :group 'pile)
 
 (defconst pile-font-lock-keywords
-  '("if" "dotimes" "while" "let" "begin" "end" "use"))
+  '("if" "dotimes" "while" "let" "begin" "end" "use" "as"))
 
 (defconst pile-font-lock-builtins
-  '("and"
+  '("abs"
+    "and"
+    "argc"
+    "argv"
     "assert"
+    "atan2"
+    "bigint"
+    "boolean"
+    "ceil"
+    "chr"
     "clear"
     "concat"
     "contains"
+    "cos"
     "downcase"
     "drop"
     "dup"
+    "e"
+    "endswith"
+    "eprint"
+    "exit"
+    "exp"
+    "find"
     "float"
+    "floor"
+    "flush"
     "format"
+    "getenv"
     "index"
     "integer"
+    "join"
     "length"
+    "ln"
+    "load"
+    "log10"
+    "max"
+    "min"
+    "mod"
     "natural"
     "not"
     "or"
+    "ord"
+    "pi"
     "pick"
+    "pow"
     "print"
+    "println"
+    "rational"
+    "readall"
+    "readfile"
+    "readfilelines"
+    "readline"
+    "readlines"
+    "recaptures"
+    "refind"
+    "rematch"
+    "repeat"
+    "replace"
+    "rereplace"
+    "reverse"
+    "round"
     "showstack"
+    "sin"
+    "split"
+    "sqrt"
     "stacksize"
-    "readlines"
+    "startswith"
+    "substring"
     "swap"
+    "tan"
+    "tostring"
     "trim"
-    "upcase"))
+    "trunc"
+    "upcase"
+    "writefile"))
 
 (defvar pile--function-regexp
   "end[[:space:]\n]*->[[:space:]\n]*\\([[:alpha:]][[:alnum:]_]*\\)")
@@ -1,3 +1,4 @@
+use crate::manifest::{Manifest, MANIFEST_FILE};
 use crate::program_source::ProgramSource;
 use crate::repl;
 
@@ -14,6 +15,7 @@ use clap::{crate_version, App, Arg, ErrorKind};
 pub struct CompletionOptions {
     pub prefix: String,
     pub line: u64,
+    pub verbose: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -21,14 +23,25 @@ pub struct CommandLineOptions {
     stack_size: usize,
     source: Rc<ProgramSource>,
     trace: bool,
+    status_from_stack: bool,
     format: bool,
     completion: Option<CompletionOptions>,
+    operator_help: Option<String>,
+    search_path: Vec<PathBuf>,
+    test_files: Option<Vec<PathBuf>>,
+    args: Vec<String>,
+    inline: Vec<String>,
+    lossy: bool,
+    keep_cr: bool,
+    strict: bool,
 }
 
 impl CommandLineOptions {
     pub fn read_program(&self) -> Result<String, String> {
         match self.source.as_ref() {
-            ProgramSource::Repl => repl::repl(),
+            ProgramSource::Repl => {
+                repl::repl(&self.search_path, &self.args, self.strict)
+            }
             ProgramSource::Stdin => {
                 let mut buffer = String::new();
                 io::stdin()
@@ -36,6 +49,7 @@ impl CommandLineOptions {
                     .map_err(|err| format!("stdin: {}", err))?;
                 Ok(buffer)
             }
+            ProgramSource::Inline => Ok(self.inline.join("\n")),
             ProgramSource::File(file) => fs::read_to_string(file)
                 .map_err(|err| format!("{}: {}", file.to_string_lossy(), err)),
         }
@@ -49,6 +63,10 @@ impl CommandLineOptions {
         self.trace
     }
 
+    pub fn status_from_stack(&self) -> bool {
+        self.status_from_stack
+    }
+
     pub fn format(&self) -> bool {
         self.format
     }
@@ -60,12 +78,93 @@ impl CommandLineOptions {
     pub fn completion(&self) -> &Option<CompletionOptions> {
         &self.completion
     }
+
+    pub fn operator_help(&self) -> Option<&str> {
+        self.operator_help.as_deref()
+    }
+
+    pub fn search_path(&self) -> &[PathBuf] {
+        &self.search_path
+    }
+
+    pub fn test_files(&self) -> Option<&[PathBuf]> {
+        self.test_files.as_deref()
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn lossy(&self) -> bool {
+        self.lossy
+    }
+
+    pub fn keep_cr(&self) -> bool {
+        self.keep_cr
+    }
+
+    pub fn strict(&self) -> bool {
+        self.strict
+    }
+}
+
+// NOTE: Directories given with '-I' are searched before the ones of the
+// project and the ones in 'PILE_PATH'.
+fn search_path<'a, I>(
+    includes: I,
+    manifest: Option<&Manifest>,
+    env: Option<OsString>,
+) -> Result<Vec<PathBuf>, String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut search_path: Vec<PathBuf> =
+        includes.into_iter().map(PathBuf::from).collect();
+
+    if let Some(manifest) = manifest {
+        search_path.extend(manifest.search_path()?);
+    }
+
+    if let Some(env) = env {
+        search_path.extend(
+            std::env::split_paths(&env)
+                .filter(|dir| !dir.as_os_str().is_empty()),
+        );
+    }
+
+    Ok(search_path)
+}
+
+fn find_manifest() -> Result<Option<Manifest>, String> {
+    let current_dir = std::env::current_dir()
+        .map_err(|err| format!("current directory: {}", err))?;
+
+    Manifest::find(&current_dir)
+        .map(|file| Manifest::read(&file))
+        .transpose()
 }
 
 pub fn read_options<I, T>(itr: I) -> Result<CommandLineOptions, String>
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
+{
+    read_options_with(itr, atty::is(Stream::Stdin), find_manifest)
+}
+
+// NOTE: The manifest is only looked up when the project is run, i.e. when
+// neither a file, an inline program nor piped input is given, or for
+// '--test'. A broken 'pile.toml' somewhere above the current directory
+// can't affect other runs this way.
+fn read_options_with<I, T, F>(
+    itr: I,
+    stdin_is_tty: bool,
+    find_manifest: F,
+) -> Result<CommandLineOptions, String>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+    F: FnOnce() -> Result<Option<Manifest>, String>,
 {
     let matches = App::new("pile")
         .version(crate_version!())
@@ -91,10 +190,62 @@ where
                 .short("t")
                 .long("trace"),
         )
+        .arg(
+            Arg::with_name("status")
+                .help(
+                    "Exit with the natural on top of the stack when the \
+                     program finishes",
+                )
+                .long("status-from-stack"),
+        )
+        .arg(
+            Arg::with_name("strict")
+                .help(
+                    "Don't convert between naturals, integers and floats in \
+                     arithmetic and comparisons",
+                )
+                .long("strict"),
+        )
+        .arg(
+            Arg::with_name("lossy")
+                .help(
+                    "Replace invalid UTF-8 in the input of the program \
+                     instead of failing",
+                )
+                .long("lossy"),
+        )
+        .arg(
+            Arg::with_name("keep-cr")
+                .help(
+                    "Keep the '\\r' of '\\r\\n' line endings in the input \
+                     of the program",
+                )
+                .long("keep-cr"),
+        )
         .arg(
             Arg::with_name("FILE")
                 .help("The program to run. Use '-' for stdin."),
         )
+        .arg(
+            Arg::with_name("eval")
+                .help(
+                    "Run <program> instead of a file. Can be given multiple \
+                     times, each <program> starts on a new line.",
+                )
+                .short("e")
+                .long("eval")
+                .value_name("program")
+                .multiple(true)
+                .number_of_values(1)
+                .allow_hyphen_values(true)
+                .conflicts_with_all(&["FILE", "repl", "test"]),
+        )
+        .arg(
+            Arg::with_name("ARGS")
+                .help("Arguments passed to the program")
+                .multiple(true)
+                .last(true),
+        )
         .arg(
             Arg::with_name("complete")
                 .help(
@@ -105,6 +256,43 @@ where
                 .value_names(&["prefix", "line"])
                 .requires("FILE"),
         )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Also print the stack effect of completed operators")
+                .long("verbose")
+                .requires("complete"),
+        )
+        .arg(
+            Arg::with_name("help-operator")
+                .help("Describe the given operator")
+                .long("help-operator")
+                .value_name("operator"),
+        )
+        .arg(
+            Arg::with_name("include")
+                .help(
+                    "Add <dir> to the directories searched by 'use'. \
+                     Searched before the ones in PILE_PATH.",
+                )
+                .short("I")
+                .long("include")
+                .value_name("dir")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("repl")
+                .help("Start the REPL even inside of a project")
+                .short("r")
+                .long("repl")
+                .conflicts_with("FILE"),
+        )
+        .arg(
+            Arg::with_name("test")
+                .help("Run the tests of the project in the current directory")
+                .long("test")
+                .conflicts_with_all(&["FILE", "repl", "complete", "format"]),
+        )
         .arg(
             Arg::with_name("format")
                 .help("Format the given program.")
@@ -128,17 +316,27 @@ where
     let stack_size: usize = matches.value_of("size").unwrap().parse().unwrap();
     let file = matches.value_of("FILE");
     let trace = matches.is_present("trace");
+    let status_from_stack = matches.is_present("status");
     let format = matches.is_present("format");
-    let source = Rc::new(match file {
-        None => {
-            if atty::is(Stream::Stdin) {
+    let inline: Vec<String> =
+        matches.values_of_lossy("eval").unwrap_or_default();
+    let runs_project = file.is_none()
+        && inline.is_empty()
+        && (stdin_is_tty || matches.is_present("test"));
+    let manifest = if runs_project { find_manifest()? } else { None };
+    let source = Rc::new(match (file, &manifest) {
+        (None, _) if !inline.is_empty() => ProgramSource::Inline,
+        (None, _) if matches.is_present("repl") => ProgramSource::Repl,
+        (None, Some(manifest)) => ProgramSource::File(manifest.entry.clone()),
+        (None, None) => {
+            if stdin_is_tty {
                 ProgramSource::Repl
             } else {
                 ProgramSource::Stdin
             }
         }
-        Some("-") => ProgramSource::Stdin,
-        Some(file) => ProgramSource::File(PathBuf::from(file)),
+        (Some("-"), _) => ProgramSource::Stdin,
+        (Some(file), _) => ProgramSource::File(PathBuf::from(file)),
     });
     let completion: Option<CompletionOptions> =
         match matches.values_of("complete") {
@@ -149,17 +347,41 @@ where
                     line: values[1].parse().map_err(|e| {
                         format!("error parsing <line> in '--complete': {}", e)
                     })?,
+                    verbose: matches.is_present("verbose"),
                 })
             }
             None => None,
         };
+    let operator_help = matches.value_of("help-operator").map(str::to_owned);
+    let search_path = search_path(
+        matches.values_of("include").into_iter().flatten(),
+        manifest.as_ref(),
+        std::env::var_os("PILE_PATH"),
+    )?;
+    let test_files = if matches.is_present("test") {
+        let manifest = manifest.as_ref().ok_or_else(|| {
+            format!("Couldn't find '{}' for '--test'.", MANIFEST_FILE)
+        })?;
+        Some(manifest.test_files()?)
+    } else {
+        None
+    };
 
     Ok(CommandLineOptions {
         stack_size,
         source,
         trace,
+        status_from_stack,
         format,
         completion,
+        operator_help,
+        search_path,
+        test_files,
+        args: matches.values_of_lossy("ARGS").unwrap_or_default(),
+        inline,
+        lossy: matches.is_present("lossy"),
+        keep_cr: matches.is_present("keep-cr"),
+        strict: matches.is_present("strict"),
     })
 }
 
@@ -1,13 +1,26 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use super::*;
 
+use std::path::Path;
+
 #[test]
 fn test_read_program() {
     let options = CommandLineOptions {
         stack_size: 100,
         source: Rc::new(ProgramSource::File(PathBuf::from("unknown.txt"))),
         trace: true,
+        status_from_stack: false,
         format: false,
         completion: None,
+        operator_help: None,
+        search_path: vec![],
+        test_files: None,
+        args: vec![],
+        inline: vec![],
+        lossy: false,
+        keep_cr: false,
+        strict: false,
     };
 
     assert_eq!(
@@ -16,22 +29,31 @@ fn test_read_program() {
     );
 }
 
-// Depending on how the test is called the source can be different.
-fn get_test_source() -> ProgramSource {
-    if atty::is(Stream::Stdin) {
-        ProgramSource::Repl
-    } else {
-        ProgramSource::Stdin
-    }
+// NOTE: The options are read as if stdin were a terminal and there were no
+// manifest, so the tests don't depend on how and where they are run.
+fn read(args: Vec<&str>) -> Result<CommandLineOptions, String> {
+    read_options_with(args, true, || Ok(None))
+}
+
+fn project_manifest() -> Result<Option<Manifest>, String> {
+    Manifest::parse(
+        "[package]\nentry = \"main.pile\"\nsources = [\"src\"]\n",
+        Path::new("/project/pile.toml"),
+    )
+    .map(Some)
+}
+
+fn broken_manifest() -> Result<Option<Manifest>, String> {
+    Err("pile.toml:1: broken".to_owned())
 }
 
 #[test]
 fn test_read_options1() -> Result<(), String> {
-    let options = read_options(vec!["test1"])?;
+    let options = read(vec!["test1"])?;
     assert_eq!(options.stack_size(), 100);
     assert_eq!(options.trace(), false);
     assert_eq!(options.format(), false);
-    assert_eq!(options.source().as_ref(), &get_test_source());
+    assert_eq!(options.source().as_ref(), &ProgramSource::Repl);
     assert_eq!(options.completion, None);
 
     Ok(())
@@ -39,7 +61,7 @@ fn test_read_options1() -> Result<(), String> {
 
 #[test]
 fn test_read_options2() -> Result<(), String> {
-    let options = read_options(vec!["test2", "-"])?;
+    let options = read(vec!["test2", "-"])?;
 
     assert_eq!(options.stack_size(), 100);
     assert_eq!(options.trace(), false);
@@ -51,7 +73,7 @@ fn test_read_options2() -> Result<(), String> {
 
 #[test]
 fn test_read_options3() -> Result<(), String> {
-    let options = read_options(vec!["test3", "./some_program.pile"])?;
+    let options = read(vec!["test3", "./some_program.pile"])?;
 
     assert_eq!(options.stack_size(), 100);
     assert_eq!(options.trace(), false);
@@ -66,19 +88,19 @@ fn test_read_options3() -> Result<(), String> {
 
 #[test]
 fn test_read_options4() -> Result<(), String> {
-    let options = read_options(vec!["test4", "-t", "--stack-size", "123"])?;
+    let options = read(vec!["test4", "-t", "--stack-size", "123"])?;
 
     assert_eq!(options.stack_size(), 123);
     assert_eq!(options.trace(), true);
     assert_eq!(options.format(), false);
-    assert_eq!(options.source().as_ref(), &get_test_source());
+    assert_eq!(options.source().as_ref(), &ProgramSource::Repl);
 
     Ok(())
 }
 
 #[test]
 fn test_read_options5() {
-    let options = read_options(vec!["test5", "-t", "--stack-size", "yes"]);
+    let options = read(vec!["test5", "-t", "--stack-size", "yes"]);
 
     assert!(options.is_err());
     assert!(options
@@ -88,10 +110,9 @@ fn test_read_options5() {
 
 #[test]
 fn test_read_completion1() -> Result<(), String> {
-    let completion =
-        read_options(vec!["test6", "-c", "prefix_", "100", "file.pile"])?
-            .completion
-            .unwrap();
+    let completion = read(vec!["test6", "-c", "prefix_", "100", "file.pile"])?
+        .completion
+        .unwrap();
 
     assert_eq!(completion.prefix, "prefix_");
     assert_eq!(completion.line, 100);
@@ -101,8 +122,7 @@ fn test_read_completion1() -> Result<(), String> {
 
 #[test]
 fn test_read_completion2() {
-    let options =
-        read_options(vec!["test7", "--complete", "var_", "nan", "file.pile"]);
+    let options = read(vec!["test7", "--complete", "var_", "nan", "file.pile"]);
 
     assert!(options.is_err());
     assert!(options
@@ -112,12 +132,155 @@ fn test_read_completion2() {
 
 #[test]
 fn test_read_format() {
-    let options = read_options(vec!["test8", "--format", "-"]);
+    let options = read(vec!["test8", "--format", "-"]);
     assert_eq!(options.unwrap().format(), true);
 
-    let options = read_options(vec!["test8", "-f", "test.pile"]);
+    let options = read(vec!["test8", "-f", "test.pile"]);
     assert_eq!(options.unwrap().format(), true);
 
-    let options = read_options(vec!["test8", "-f"]);
+    let options = read(vec!["test8", "-f"]);
     assert!(options.is_err());
 }
+
+#[test]
+fn test_read_completion_verbose() -> Result<(), String> {
+    let completion =
+        read(vec!["test9", "-c", "pri", "1", "--verbose", "file.pile"])?
+            .completion
+            .unwrap();
+
+    assert!(completion.verbose);
+
+    let options = read(vec!["test9", "--verbose", "file.pile"]);
+    assert!(options.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_operator_help() -> Result<(), String> {
+    let options = read(vec!["test10", "--help-operator", "dup"])?;
+    assert_eq!(options.operator_help(), Some("dup"));
+
+    let options = read(vec!["test10"])?;
+    assert_eq!(options.operator_help(), None);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_include() -> Result<(), String> {
+    let options = read(vec!["test11", "-I", "lib", "--include", "/usr/lib"])?;
+    assert!(options
+        .search_path()
+        .starts_with(&[PathBuf::from("lib"), PathBuf::from("/usr/lib")]));
+
+    Ok(())
+}
+
+#[test]
+fn test_search_path() {
+    assert_eq!(
+        search_path(vec!["a", "b"], None, Some(OsString::from("c::d"))),
+        Ok(vec![
+            PathBuf::from("a"),
+            PathBuf::from("b"),
+            PathBuf::from("c"),
+            PathBuf::from("d")
+        ])
+    );
+    assert_eq!(search_path(vec![], None, None), Ok(vec![]));
+}
+
+#[test]
+fn test_read_args() -> Result<(), String> {
+    let options = read(vec!["test12", "file.pile", "--", "-x", "--flag", "y"])?;
+    assert_eq!(options.args(), &["-x", "--flag", "y"]);
+
+    let options = read(vec!["test12", "file.pile"])?;
+    assert!(options.args().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_inline() -> Result<(), String> {
+    let options = read(vec!["test13", "-e", "1 2 +", "--eval", "print"])?;
+    assert_eq!(options.source(), Rc::new(ProgramSource::Inline));
+    assert_eq!(options.read_program(), Ok("1 2 +\nprint".to_owned()));
+
+    let options = read(vec!["test13", "-e", "-1 print"])?;
+    assert_eq!(options.read_program(), Ok("-1 print".to_owned()));
+
+    let options = read(vec!["test13", "-e", "1", "file.pile"]);
+    assert!(options.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_input_mode() -> Result<(), String> {
+    let options = read(vec!["test14", "file.pile"])?;
+    assert!(!options.lossy());
+    assert!(!options.keep_cr());
+
+    let options = read(vec!["test14", "--lossy", "--keep-cr", "file.pile"])?;
+    assert!(options.lossy());
+    assert!(options.keep_cr());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_strict() -> Result<(), String> {
+    assert!(!read(vec!["test15", "file.pile"])?.strict());
+    assert!(read(vec!["test15", "--strict", "file.pile"])?.strict());
+
+    Ok(())
+}
+
+#[test]
+fn test_read_project() -> Result<(), String> {
+    let options = read_options_with(vec!["test16"], true, project_manifest)?;
+    assert_eq!(
+        options.source().as_ref(),
+        &ProgramSource::File(PathBuf::from("/project/main.pile"))
+    );
+    assert_eq!(options.search_path(), &[PathBuf::from("/project/src")]);
+
+    let options =
+        read_options_with(vec!["test16", "-r"], true, project_manifest)?;
+    assert_eq!(options.source().as_ref(), &ProgramSource::Repl);
+
+    Ok(())
+}
+
+#[test]
+fn test_read_without_project() -> Result<(), String> {
+    let options = read_options_with(vec!["test17"], false, broken_manifest)?;
+    assert_eq!(options.source().as_ref(), &ProgramSource::Stdin);
+    assert!(options.search_path().is_empty());
+
+    let options =
+        read_options_with(vec!["test17", "file.pile"], true, broken_manifest)?;
+    assert!(options.search_path().is_empty());
+
+    let options =
+        read_options_with(vec!["test17", "-e", "1"], true, broken_manifest)?;
+    assert_eq!(options.source().as_ref(), &ProgramSource::Inline);
+
+    assert_eq!(
+        read_options_with(vec!["test17"], true, broken_manifest),
+        Err("pile.toml:1: broken".to_owned())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_read_test_without_manifest() {
+    assert_eq!(
+        read_options_with(vec!["test18", "--test"], false, || Ok(None)),
+        Err("Couldn't find 'pile.toml' for '--test'.".to_owned())
+    );
+}
@@ -1,4 +1,4 @@
-use crate::lex::{Operator, Token};
+use crate::lex::{OperatorInfo, Token, OPERATORS};
 use crate::parse::Expr;
 use crate::using::ResolvedAst;
 
@@ -12,6 +12,8 @@ macro_rules! ensure_token_completion {
         fn ensure_all_covered(tok: Token) {
             match tok {
                 $($token             => {})+,
+                // operators are covered by the operator table
+                Token::Operator(_)   => {},
                 // the following tokens can't be known at compile time
                 Token::Number(_)     => {},
                 Token::Identifier(_) => {},
@@ -25,51 +27,16 @@ macro_rules! ensure_token_completion {
 
 fn token_completions() -> &'static [&'static str] {
     ensure_token_completion! {
-        Token::Operator(Operator::If)           => "if",
-        Token::Operator(Operator::Dotimes)      => "dotimes",
-        Token::Operator(Operator::While)        => "while",
-        Token::Operator(Operator::Plus)         => "+",
-        Token::Operator(Operator::Minus)        => "-",
-        Token::Operator(Operator::Div)          => "/",
-        Token::Operator(Operator::Mul)          => "*",
-        Token::Operator(Operator::Greater)      => ">",
-        Token::Operator(Operator::GreaterEqual) => ">=",
-        Token::Operator(Operator::Equal)        => "=",
-        Token::Operator(Operator::LessEqual)    => "<=",
-        Token::Operator(Operator::And)          => "and",
-        Token::Operator(Operator::Or)           => "or",
-        Token::Operator(Operator::Not)          => "not",
-        Token::Operator(Operator::Less)         => "<",
-        Token::Operator(Operator::Print)        => "print",
-        Token::Operator(Operator::Showstack)    => "showstack",
-        Token::Operator(Operator::Assert)       => "assert",
-        Token::Operator(Operator::Dup)          => "dup",
-        Token::Operator(Operator::Drop)         => "drop",
-        Token::Operator(Operator::Swap)         => "swap",
-        Token::Operator(Operator::Pick)         => "pick",
-        Token::Operator(Operator::Clear)        => "clear",
-        Token::Operator(Operator::StackSize)    => "stacksize",
-        Token::Operator(Operator::ReadLines)    => "readlines",
-        Token::Operator(Operator::Natural)      => "natural",
-        Token::Operator(Operator::Integer)      => "integer",
-        Token::Operator(Operator::Float)        => "float",
-        Token::Operator(Operator::Concat)       => "concat",
-        Token::Operator(Operator::Length)       => "length",
-        Token::Operator(Operator::Contains)     => "contains",
-        Token::Operator(Operator::Upcase)       => "upcase",
-        Token::Operator(Operator::Downcase)     => "downcase",
-        Token::Operator(Operator::Trim)         => "trim",
-        Token::Operator(Operator::Format)       => "format",
-        Token::Operator(Operator::Index)        => "index",
-        Token::Assign                           => "->",
-        Token::Begin                            => "begin",
-        Token::End                              => "end",
-        Token::Let                              => "let",
-        Token::BracketLeft                      => "[",
-        Token::BracketRight                     => "]",
-        Token::Boolean(true)                    => "true",
-        Token::Boolean(false)                   => "false",
-        Token::Use                              => "use",
+        Token::Assign         => "->",
+        Token::Begin          => "begin",
+        Token::End            => "end",
+        Token::Let            => "let",
+        Token::BracketLeft    => "[",
+        Token::BracketRight   => "]",
+        Token::Boolean(true)  => "true",
+        Token::Boolean(false) => "false",
+        Token::Use            => "use",
+        Token::As             => "as",
     }
 }
 
@@ -103,7 +70,8 @@ fn map_identifiers<O>(
             Expr::Use {
                 subprogram,
                 line: use_line,
-            } if *use_line <= line => {
+                ..
+            } if *use_line <= line && line <= range.1 => {
                 map_sub_identifiers(&subprogram.expressions, operation)
             }
             _ => (),
@@ -143,6 +111,10 @@ pub fn map_completions<O>(
         }
     };
 
+    for info in OPERATORS {
+        filter(info.name)
+    }
+
     for token in token_completions() {
         filter(token)
     }
@@ -150,7 +122,21 @@ pub fn map_completions<O>(
     map_identifiers(&ast.as_ref().expressions, (0, u64::MAX), line, &mut filter)
 }
 
-pub fn complete_to_stdout(prefix: &str, line: u64, ast: &ResolvedAst) {
+pub fn operator_info(name: &str) -> Option<&'static OperatorInfo> {
+    OPERATORS.iter().find(|info| info.name == name)
+}
+
+pub fn operator_hint(word: &str) -> Option<String> {
+    operator_info(word)
+        .map(|info| format!("  # {} {}", info.stack_effect, info.description))
+}
+
+pub fn complete_to_stdout(
+    prefix: &str,
+    line: u64,
+    verbose: bool,
+    ast: &ResolvedAst,
+) {
     let mut completions = HashSet::new();
 
     map_completions(prefix, line, ast, &mut |name| {
@@ -158,7 +144,13 @@ pub fn complete_to_stdout(prefix: &str, line: u64, ast: &ResolvedAst) {
     });
 
     for name in completions {
-        println!("{}", name);
+        match operator_info(&name) {
+            Some(info) if verbose => println!(
+                "{}\t{}\t{}",
+                name, info.stack_effect, info.description
+            ),
+            _ => println!("{}", name),
+        }
     }
 }
 
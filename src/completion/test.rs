@@ -10,7 +10,7 @@ use std::rc::Rc;
 fn parse_prog(text: &str) -> ResolvedAst {
     let ast =
         Parser::new(Lexer::new(text, Rc::new(ProgramSource::Stdin))).parse();
-    let ast = using::resolve(locals::translate(ast.unwrap()));
+    let ast = using::resolve(locals::translate(ast.unwrap()), &[]);
     ast.unwrap()
 }
 
@@ -163,19 +163,88 @@ fn test_comp_prefix1() {
 fn test_comp_prefix2() {
     let ast = parse_prog("");
     let comps = complete_to_vec("pri", 1, &ast);
-    assert_eq!(comps, vec!["print"])
+    assert_eq!(comps, vec!["print", "println"])
 }
 
 #[test]
 fn test_comp_prefix3() {
     let ast = parse_prog("");
     let comps = complete_to_vec("l", 1, &ast);
-    assert_eq!(comps, vec!["length", "let"])
+    assert_eq!(comps, vec!["ln", "log10", "load", "length", "let"])
 }
 
 #[test]
 fn test_comp_prefix4() {
     let ast = parse_prog("");
     let comps = complete_to_vec("f", 1, &ast);
-    assert_eq!(comps, vec!["float", "format", "false"])
+    assert_eq!(
+        comps,
+        vec!["floor", "flush", "float", "format", "find", "false"]
+    )
+}
+
+#[test]
+fn test_operator_hint() {
+    assert_eq!(
+        operator_hint("dup"),
+        Some("  # ( a -- a a ) Duplicates the top of the stack.".to_owned())
+    );
+    assert_eq!(operator_hint("begin"), None);
+    assert_eq!(operator_hint("du"), None);
+}
+
+#[test]
+fn test_comp_use_namespace() {
+    let mut comps = vec![];
+    let ast = parse_prog(
+        r#"
+use "src/completion/comp_test" as ct
+# --- current line ---
+"#,
+    );
+
+    map_identifiers(&ast.ast().expressions, (0, u64::MAX), 3, &mut |ident| {
+        comps.push(ident.to_owned())
+    });
+
+    assert_eq!(comps, vec!["ct.value", "ct.inc", "ct.dec"])
+}
+
+#[test]
+fn test_comp_use_names() {
+    let ast = parse_prog(
+        r#"
+use "src/completion/comp_test" [inc]
+# --- current line ---
+"#,
+    );
+
+    assert_eq!(complete_to_vec("inc", 3, &ast), vec!["inc"]);
+
+    assert_eq!(
+        complete_to_vec("src.completion.comp_test.", 3, &ast),
+        vec![
+            "src.completion.comp_test.value",
+            "src.completion.comp_test.dec"
+        ]
+    );
+}
+
+#[test]
+fn test_comp_use_in_block() {
+    let ast = parse_prog(
+        r#"
+begin
+    use "src/completion/comp_test" as ct
+    # --- current line ---
+end
+# --- outside of the block ---
+"#,
+    );
+
+    assert_eq!(
+        complete_to_vec("ct.", 4, &ast),
+        vec!["ct.value", "ct.inc", "ct.dec"]
+    );
+    assert_eq!(complete_to_vec("ct.", 6, &ast), Vec::<String>::new());
 }
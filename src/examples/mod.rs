@@ -16,7 +16,7 @@ fn run_example_file(path: PathBuf) {
     let parser = Parser::new(lexer);
     let ast = parser.parse().unwrap();
     let ast = locals::translate(ast);
-    let ast = using::resolve(ast).unwrap();
+    let ast = using::resolve(ast, &[]).unwrap();
 
     let mut interpreter = Interpreter::new(ast, 100, false);
     interpreter.run().expect("Test program failed!");
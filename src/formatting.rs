@@ -1,4 +1,5 @@
-use crate::lex::{Lexer, LexerItem, Token};
+use crate::lex::{self, Lexer, LexerItem, Token};
+use crate::parse::{Ast, Expr};
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
 
@@ -16,7 +17,7 @@ pub fn format(lexer: Lexer) -> Result<(), PileError> {
 
     match lexer.source().as_ref() {
         ProgramSource::Repl => panic!("Can't format repl program!"),
-        ProgramSource::Stdin => {
+        ProgramSource::Stdin | ProgramSource::Inline => {
             write_formatting(&mut content, lexer)?;
             io::stdout()
                 .write_all(&content)
@@ -65,7 +66,7 @@ where
         let token = token?;
 
         if let Token::End = token {
-            indent_level = indent_level.checked_sub(1).unwrap_or_default();
+            indent_level = indent_level.saturating_sub(1);
         }
 
         if prev_line < line {
@@ -100,5 +101,123 @@ where
     Ok(())
 }
 
+pub fn format_text(
+    text: &str,
+    source: &Rc<ProgramSource>,
+) -> Result<String, PileError> {
+    let mut content = Vec::<u8>::with_capacity(FILE_BUFFER_SIZE);
+
+    write_formatting(&mut content, Lexer::new(text, Rc::clone(source)))?;
+
+    Ok(String::from_utf8(content).expect("formatting produced invalid utf-8"))
+}
+
+pub fn format_ast(ast: &Ast) -> Result<String, PileError> {
+    let mut unparser = Unparser::default();
+    unparser.expressions(&ast.expressions, true);
+
+    format_text(&unparser.text, &ast.source)
+}
+
+pub fn function_source(expressions: &[Expr]) -> String {
+    let first_line = expressions.first().map_or(1, |expr| expr.lines().0);
+    let last_line = expressions.last().map_or(1, |expr| expr.lines().1);
+    let mut unparser = Unparser::default();
+
+    unparser.block(first_line, last_line, &[], expressions);
+    unparser.text
+}
+
+// NOTE: Turns an ast back into pile source. The lines of the expressions
+// decide where line breaks are placed, `write_formatting` takes care of the
+// indentation afterwards.
+#[derive(Default)]
+struct Unparser {
+    text: String,
+    line: Option<u64>,
+}
+
+impl Unparser {
+    fn word(&mut self, line: u64, word: &str) {
+        if !self.text.is_empty() {
+            if self.line == Some(line) {
+                self.text.push(' ');
+            } else {
+                self.text.push('\n');
+            }
+        }
+
+        self.text.push_str(word);
+        self.line = Some(line);
+    }
+
+    fn break_line(&mut self) {
+        self.line = None;
+    }
+
+    fn expressions(&mut self, expressions: &[Expr], top_level: bool) {
+        for expr in expressions {
+            match expr {
+                Expr::Atom { line, token } => {
+                    self.word(*line, &token.source_fmt())
+                }
+                Expr::Assignment { line, var } => {
+                    self.word(*line, &format!("-> {}", var))
+                }
+                Expr::Block {
+                    begin,
+                    end,
+                    locals,
+                    expressions,
+                } => self.block(*begin, *end, locals, expressions),
+                Expr::Use {
+                    line, file, import, ..
+                } => self.word(
+                    *line,
+                    &format!(
+                        "use {}{}",
+                        lex::string_source_fmt(&file.to_string_lossy()),
+                        import
+                    ),
+                ),
+                Expr::Save { .. } | Expr::Restore { .. } => {
+                    // NOTE: part of the 'let' header of the enclosing block
+                }
+            }
+
+            if top_level
+                && matches!(expr, Expr::Assignment { .. } | Expr::Use { .. })
+            {
+                self.break_line();
+            }
+        }
+    }
+
+    fn block(
+        &mut self,
+        begin: u64,
+        end: u64,
+        locals: &[String],
+        expressions: &[Expr],
+    ) {
+        let saved = expressions.iter().filter_map(|expr| match expr {
+            Expr::Save { var, .. } => Some(var),
+            _ => None,
+        });
+        let locals: Vec<&String> = locals.iter().chain(saved).collect();
+
+        if locals.is_empty() {
+            self.word(begin, "begin");
+        } else {
+            let locals: Vec<&str> =
+                locals.iter().map(|var| var.as_str()).collect();
+            self.word(begin, &format!("let [{}]", locals.join(" ")));
+        }
+
+        self.expressions(expressions, false);
+        self.word(end, "end");
+    }
+}
+
 #[cfg(test)]
 mod test;
@@ -263,3 +263,47 @@ end
         .to_string()
         .ends_with("format_a.pile:4: Unknown operator '>>'"));
 }
+
+fn expect_unparsing(original: &str, formatted: &str) {
+    let lexer = Lexer::new(original, Rc::new(ProgramSource::Stdin));
+    let ast = crate::parse::Parser::new(lexer).parse().unwrap();
+    let ast = crate::locals::translate(ast).ast();
+
+    let result = format_ast(&ast).unwrap();
+    if result != formatted {
+        eprintln!("===\n{}\n===\n{}\n===", result, formatted);
+        assert_eq!(result, formatted);
+    }
+}
+
+#[test]
+fn test_unparse_program() {
+    expect_unparsing("1 2 + print", "1 2 + print\n");
+    expect_unparsing("1 -> a 2 -> b", "1 -> a\n2 -> b\n");
+    expect_unparsing(
+        "# comment\n\"a\\tb\" 1.0 -2 false",
+        "\"a\\tb\" 1.0 -2 false\n",
+    );
+    expect_unparsing(
+        "let [n result]\n-> n\nbegin n 1 - end\nend -> f",
+        "let [n result]\n    -> n\n    begin n 1 - end\nend -> f\n",
+    );
+    expect_unparsing(
+        "begin let [x]\n-> x x end end",
+        "begin let [x]\n        -> x x end end\n",
+    );
+}
+
+#[test]
+fn test_function_source() {
+    let lexer = Lexer::new("let [a] -> a a end", Rc::new(ProgramSource::Stdin));
+    let ast = crate::parse::Parser::new(lexer).parse().unwrap();
+    let ast = crate::locals::translate(ast).ast();
+
+    match &ast.expressions[0] {
+        Expr::Block { expressions, .. } => {
+            assert_eq!(function_source(expressions), "let [a] -> a a end")
+        }
+        expr => panic!("unexpected expression {:?}", expr),
+    }
+}
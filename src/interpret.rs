@@ -1,18 +1,31 @@
+use crate::formatting;
 use crate::lex::Token;
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
-use crate::using::ResolvedAst;
+use crate::using::{ModuleCache, ResolvedAst};
 
 mod runtime_value;
+pub use runtime_value::RuntimeValue;
 use runtime_value::*;
 mod assert;
 mod boolean;
 mod cast;
 mod condition;
 mod dotimes;
+mod environment;
+mod exit;
+mod file;
+mod format;
+pub use exit::exit_status;
+mod loader;
 mod numeric;
+mod output;
+mod pattern;
+use output::Output;
+use pattern::RegexCache;
 mod print;
 mod reader;
+use reader::InputMode;
 mod runtime_error;
 mod scoping;
 mod stackop;
@@ -21,6 +34,8 @@ mod tracer;
 mod while_loop;
 use scoping::ScopeStack;
 
+use std::collections::HashSet;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 pub struct State {
@@ -28,6 +43,14 @@ pub struct State {
     lookup: ScopeStack,
     current_lines: (u64, u64),
     trace: bool,
+    modules: HashSet<Rc<ProgramSource>>,
+    module_cache: ModuleCache,
+    search_path: Vec<PathBuf>,
+    args: Vec<String>,
+    output: Output,
+    input_mode: InputMode,
+    strict: bool,
+    regexes: RegexCache,
 }
 
 pub struct Interpreter {
@@ -37,10 +60,12 @@ pub struct Interpreter {
 
 impl Interpreter {
     pub fn new(
-        program: ResolvedAst,
+        mut program: ResolvedAst,
         initial_size: usize,
         trace: bool,
     ) -> Interpreter {
+        let module_cache = program.take_module_cache();
+
         Interpreter {
             program,
             state: State {
@@ -48,6 +73,14 @@ impl Interpreter {
                 lookup: ScopeStack::new(),
                 current_lines: (1, 1),
                 trace,
+                modules: HashSet::new(),
+                module_cache,
+                search_path: vec![],
+                args: vec![],
+                output: Output::standard(),
+                input_mode: InputMode::default(),
+                strict: false,
+                regexes: RegexCache::new(),
             },
         }
     }
@@ -60,6 +93,14 @@ impl Interpreter {
                 lookup: ScopeStack::new(),
                 current_lines: (1, 1),
                 trace: false,
+                modules: HashSet::new(),
+                module_cache: ModuleCache::default(),
+                search_path: vec![],
+                args: vec![],
+                output: Output::standard(),
+                input_mode: InputMode::default(),
+                strict: false,
+                regexes: RegexCache::new(),
             },
         }
     }
@@ -68,6 +109,65 @@ impl Interpreter {
         &self.program
     }
 
+    pub fn is_bound(&self, name: &str) -> bool {
+        self.state.lookup.resolve(name).is_some()
+    }
+
+    pub fn show_stack(&mut self) -> Result<(), String> {
+        print::apply_showstack(&mut self.state.stack, &mut self.state.output)
+    }
+
+    pub fn show_variables(&mut self) -> Result<(), String> {
+        let mut variables: Vec<_> = self.state.lookup.bindings().collect();
+        variables.sort_by_key(|(name, _)| *name);
+        print::show_variables(&variables, &mut self.state.output)
+    }
+
+    pub fn set_trace(&mut self, trace: bool) {
+        self.state.trace = trace;
+    }
+
+    pub fn set_search_path(&mut self, search_path: &[PathBuf]) {
+        self.state.search_path = search_path.to_vec();
+    }
+
+    pub fn set_args(&mut self, args: &[String]) {
+        self.state.args = args.to_vec();
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.state.strict = strict;
+    }
+
+    pub fn set_input_mode(&mut self, lossy: bool, keep_cr: bool) {
+        self.state.input_mode = InputMode { lossy, keep_cr };
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.state.output.flush()
+    }
+
+    pub fn snapshot(&self) -> Result<String, PileError> {
+        let mut variables: Vec<_> = self.state.lookup.bindings().collect();
+        variables.sort_by_key(|(name, _)| *name);
+
+        let mut text = String::new();
+        for (name, value) in variables {
+            text += &format!("{} -> {}\n", value.source_fmt(), name);
+        }
+        for value in &self.state.stack {
+            text += &format!("{}\n", value.source_fmt());
+        }
+
+        formatting::format_text(&text, &self.program.as_ref().source)
+    }
+
+    pub fn reset(&mut self) {
+        self.program = ResolvedAst::repl_ast();
+        self.state.stack.clear();
+        self.state.lookup = ScopeStack::new();
+    }
+
     pub fn run(&mut self) -> Result<Option<&RuntimeValue>, PileError> {
         Interpreter::call(
             &self.program.as_ref().expressions,
@@ -79,16 +179,23 @@ impl Interpreter {
 
     pub fn eval(
         &mut self,
-        ast: ResolvedAst,
+        mut ast: ResolvedAst,
     ) -> Result<Option<&RuntimeValue>, PileError> {
         let old_size = self.program.as_ref().expressions.len();
+        self.state.module_cache = ast.take_module_cache();
         self.program.append(ast);
+        // NOTE: Every evaluation is a new run, so used modules run again.
+        self.state.modules.clear();
 
-        Interpreter::call(
+        // NOTE: Lines that fail aren't kept, so ':save' writes only what ran.
+        if let Err(err) = Interpreter::call(
             &self.program.as_ref().expressions[old_size..],
             &mut self.state,
             &self.program.as_ref().source,
-        )?;
+        ) {
+            self.program.truncate(old_size);
+            return Err(err);
+        }
         Ok(self.state.stack.last())
     }
 
@@ -101,7 +208,7 @@ impl Interpreter {
             state.current_lines = expr.lines();
 
             if state.trace {
-                tracer::before_eval(expr, &state.lookup);
+                tracer::before_eval(expr, &state.lookup, state.output.stdout());
             }
 
             let result = match expr {
@@ -140,11 +247,17 @@ impl Interpreter {
                     }));
                     Ok(())
                 }
-                Expr::Use { subprogram, .. } => Interpreter::call(
-                    &subprogram.expressions,
-                    state,
-                    &subprogram.source,
-                ),
+                Expr::Use { subprogram, .. } => {
+                    if state.modules.insert(Rc::clone(&subprogram.source)) {
+                        Interpreter::call(
+                            &subprogram.expressions,
+                            state,
+                            &subprogram.source,
+                        )
+                    } else {
+                        Ok(())
+                    }
+                }
                 Expr::Save { var, .. } => {
                     state.lookup.save(var);
                     Ok(())
@@ -156,7 +269,7 @@ impl Interpreter {
             };
 
             if state.trace {
-                tracer::after_eval(expr);
+                tracer::after_eval(expr, &state.lookup, state.output.stdout());
             }
 
             if let Err(e) = result {
@@ -167,13 +280,21 @@ impl Interpreter {
                     .for_each(|expr| {
                         if let Expr::Restore { var, .. } = expr {
                             if state.trace {
-                                tracer::before_eval(expr, &state.lookup);
+                                tracer::before_eval(
+                                    expr,
+                                    &state.lookup,
+                                    state.output.stdout(),
+                                );
                             }
 
                             state.lookup.restore(var);
 
                             if state.trace {
-                                tracer::after_eval(expr);
+                                tracer::after_eval(
+                                    expr,
+                                    &state.lookup,
+                                    state.output.stdout(),
+                                );
                             }
                         }
                     });
@@ -191,24 +312,88 @@ impl Interpreter {
     ) -> Result<(), PileError> {
         let stack = &mut state.stack;
         let operation_result = match op {
-            Operator::Plus => numeric::apply_plus(stack),
-            Operator::Minus => numeric::apply_minus(stack),
-            Operator::Mul => numeric::apply_mul(stack),
-            Operator::Div => numeric::apply_div(stack),
+            Operator::Plus => numeric::apply_plus(stack, state.strict),
+            Operator::Minus => numeric::apply_minus(stack, state.strict),
+            Operator::Mul => numeric::apply_mul(stack, state.strict),
+            Operator::Div => numeric::apply_div(stack, state.strict),
+            Operator::Rem => numeric::apply_rem(stack, state.strict),
+            Operator::Mod => numeric::apply_mod(stack, state.strict),
+            Operator::Pow => numeric::apply_pow(stack, state.strict),
+            Operator::Min => numeric::apply_min(stack, state.strict),
+            Operator::Max => numeric::apply_max(stack, state.strict),
+            Operator::Abs => numeric::apply_abs(stack, state.strict),
+            Operator::Floor => numeric::apply_floor(stack),
+            Operator::Ceil => numeric::apply_ceil(stack),
+            Operator::Round => numeric::apply_round(stack),
+            Operator::Trunc => numeric::apply_trunc(stack),
+            Operator::Sqrt => numeric::apply_sqrt(stack),
+            Operator::Sin => numeric::apply_sin(stack),
+            Operator::Cos => numeric::apply_cos(stack),
+            Operator::Tan => numeric::apply_tan(stack),
+            Operator::Atan2 => numeric::apply_atan2(stack),
+            Operator::Exp => numeric::apply_exp(stack),
+            Operator::Ln => numeric::apply_ln(stack),
+            Operator::Log10 => numeric::apply_log10(stack),
+            Operator::Pi => numeric::apply_pi(stack),
+            Operator::E => numeric::apply_e(stack),
             Operator::If => return condition::apply_if(state, source),
-            Operator::Less => boolean::apply_less(stack),
-            Operator::LessEqual => boolean::apply_less_equal(stack),
-            Operator::Equal => boolean::apply_equal(stack),
-            Operator::Greater => boolean::apply_greater(stack),
-            Operator::GreaterEqual => boolean::apply_greater_equal(stack),
+            Operator::Less => boolean::apply_less(stack, state.strict),
+            Operator::LessEqual => {
+                boolean::apply_less_equal(stack, state.strict)
+            }
+            Operator::Equal => boolean::apply_equal(stack, state.strict),
+            Operator::Greater => boolean::apply_greater(stack, state.strict),
+            Operator::GreaterEqual => {
+                boolean::apply_greater_equal(stack, state.strict)
+            }
             Operator::And => boolean::apply_and(stack),
             Operator::Or => boolean::apply_or(stack),
             Operator::Not => boolean::apply_not(stack),
-            Operator::Print => print::apply_print(stack),
-            Operator::Showstack => print::apply_showstack(stack),
+            Operator::Print => print::apply_print(stack, &mut state.output),
+            Operator::Println => print::apply_println(stack, &mut state.output),
+            Operator::Eprint => print::apply_eprint(stack, &mut state.output),
+            Operator::Flush => print::apply_flush(&mut state.output),
+            Operator::Showstack => {
+                print::apply_showstack(stack, &mut state.output)
+            }
             Operator::ReadLines => {
                 return reader::apply_readlines(state, source);
             }
+            Operator::Load => return loader::apply_load(state, source),
+            Operator::Exit => {
+                let status = exit::apply_exit(stack, &mut state.output)
+                    .map_err(|msg| {
+                        PileError::in_range(
+                            Rc::clone(source),
+                            state.current_lines,
+                            msg,
+                        )
+                    })?;
+                return Err(PileError::exit(
+                    Rc::clone(source),
+                    state.current_lines,
+                    status,
+                ));
+            }
+            Operator::ReadLine => reader::apply_readline(
+                stack,
+                &mut state.output,
+                state.input_mode,
+            ),
+            Operator::ReadAll => reader::apply_readall(
+                stack,
+                &mut state.output,
+                state.input_mode,
+            ),
+            Operator::ReadFile => file::apply_readfile(stack, state.input_mode),
+            Operator::WriteFile => file::apply_writefile(stack),
+            Operator::AppendFile => file::apply_appendfile(stack),
+            Operator::ReadFileLines => {
+                return reader::apply_readfilelines(state, source);
+            }
+            Operator::Argc => environment::apply_argc(stack, &state.args),
+            Operator::Argv => environment::apply_argv(stack, &state.args),
+            Operator::Getenv => environment::apply_getenv(stack),
             Operator::Assert => assert::apply_assert(stack),
             Operator::Dup => stackop::apply_dup(stack),
             Operator::Drop => stackop::apply_drop(stack),
@@ -220,15 +405,42 @@ impl Interpreter {
             Operator::While => return while_loop::apply_while(state, source),
             Operator::Natural => cast::apply_natural(stack),
             Operator::Integer => cast::apply_integer(stack),
+            Operator::BigInt => cast::apply_bigint(stack),
+            Operator::Rational => cast::apply_rational(stack),
             Operator::Float => cast::apply_float(stack),
+            Operator::Boolean => cast::apply_boolean(stack),
+            Operator::ToString => cast::apply_tostring(stack),
             Operator::Concat => string::apply_concat(stack),
             Operator::Length => string::apply_length(stack),
             Operator::Contains => string::apply_contains(stack),
             Operator::Downcase => string::apply_downcase(stack),
             Operator::Upcase => string::apply_upcase(stack),
             Operator::Trim => string::apply_trim(stack),
-            Operator::Format => string::apply_format(stack),
+            Operator::Format => format::apply_format(stack),
             Operator::Index => string::apply_index(stack),
+            Operator::Split => string::apply_split(stack),
+            Operator::Join => string::apply_join(stack),
+            Operator::Replace => string::apply_replace(stack),
+            Operator::Substring => string::apply_substring(stack),
+            Operator::StartsWith => string::apply_startswith(stack),
+            Operator::EndsWith => string::apply_endswith(stack),
+            Operator::Find => string::apply_find(stack),
+            Operator::Repeat => string::apply_repeat(stack),
+            Operator::Reverse => string::apply_reverse(stack),
+            Operator::Ord => string::apply_ord(stack),
+            Operator::Chr => string::apply_chr(stack),
+            Operator::ReMatch => {
+                pattern::apply_rematch(stack, &mut state.regexes)
+            }
+            Operator::ReFind => {
+                pattern::apply_refind(stack, &mut state.regexes)
+            }
+            Operator::ReCaptures => {
+                pattern::apply_recaptures(stack, &mut state.regexes)
+            }
+            Operator::ReReplace => {
+                pattern::apply_rereplace(stack, &mut state.regexes)
+            }
         };
 
         operation_result.map_err(|msg| {
@@ -250,6 +462,8 @@ impl Interpreter {
                 value => state.stack.push(value),
             }
             Ok(())
+        } else if let Some(op) = Operator::from_name(ident) {
+            Interpreter::apply(&op, state, source)
         } else {
             Err(PileError::in_range(
                 Rc::clone(source),
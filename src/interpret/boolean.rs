@@ -1,3 +1,4 @@
+use super::numeric;
 use super::runtime_error;
 use super::runtime_value::RuntimeValue;
 
@@ -6,11 +7,19 @@ use std::cmp::Ordering;
 fn apply_ordering(
     orderings: &[Ordering],
     stack: &mut Vec<RuntimeValue>,
+    strict: bool,
 ) -> Result<(), String> {
     let right = runtime_error::ensure_element(stack)?;
     let left = runtime_error::ensure_element(stack)?;
 
-    let compare_result = match left.partial_cmp(&right) {
+    let ordering = match (&left, &right) {
+        (RuntimeValue::Number(l), RuntimeValue::Number(r)) if !strict => {
+            numeric::compare(l, r)
+        }
+        _ => left.partial_cmp(&right),
+    };
+
+    let compare_result = match ordering {
         None => {
             return Err(format!(
                 "Can't compare {} and {}",
@@ -26,26 +35,39 @@ fn apply_ordering(
     Ok(())
 }
 
-pub fn apply_less(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    apply_ordering(&[Ordering::Less], stack)
+pub fn apply_less(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_ordering(&[Ordering::Less], stack, strict)
 }
 
-pub fn apply_less_equal(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    apply_ordering(&[Ordering::Less, Ordering::Equal], stack)
+pub fn apply_less_equal(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_ordering(&[Ordering::Less, Ordering::Equal], stack, strict)
 }
 
-pub fn apply_equal(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    apply_ordering(&[Ordering::Equal], stack)
+pub fn apply_equal(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_ordering(&[Ordering::Equal], stack, strict)
 }
 
-pub fn apply_greater(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    apply_ordering(&[Ordering::Greater], stack)
+pub fn apply_greater(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_ordering(&[Ordering::Greater], stack, strict)
 }
 
 pub fn apply_greater_equal(
     stack: &mut Vec<RuntimeValue>,
+    strict: bool,
 ) -> Result<(), String> {
-    apply_ordering(&[Ordering::Greater, Ordering::Equal], stack)
+    apply_ordering(&[Ordering::Greater, Ordering::Equal], stack, strict)
 }
 
 pub fn apply_and(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
@@ -1,21 +1,54 @@
 use super::runtime_error;
 use super::runtime_value::RuntimeValue;
-use crate::lex::Number;
+use crate::lex::{self, BigInt, Number, Rational};
 
 use std::convert::TryInto;
 
+// NOTE: Strings are parsed like number literals in a program. Surrounding
+// whitespace is ignored, so lines read from input can be used directly.
+fn ensure_number_or_string(
+    stack: &mut Vec<RuntimeValue>,
+) -> Result<Number, String> {
+    match stack.last() {
+        Some(RuntimeValue::String(_)) => {
+            let s = runtime_error::ensure_string(stack)?;
+            lex::parse_number(s.trim())
+        }
+        Some(RuntimeValue::Number(_)) | None => {
+            runtime_error::ensure_number(stack)
+        }
+        Some(_) => {
+            let v = runtime_error::ensure_element(stack)?;
+            Err(format!("Expected number or string found {}", v.type_fmt()))
+        }
+    }
+}
+
 pub fn apply_natural(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    let element = runtime_error::ensure_number(stack)?;
+    let element = ensure_number_or_string(stack)?;
+    let invalid = |n: &Number| {
+        format!("Conversion from {} to natural is invalid", n.type_fmt())
+    };
 
     match element {
         Number::Natural(n) => {
             stack.push(RuntimeValue::Number(Number::Natural(n)))
         }
-        Number::Integer(i) => stack.push(RuntimeValue::Number(
-            Number::Natural(i.try_into().map_err(|_| {
-                format!("Conversion from integer '{}' to natural is invalid", i)
-            })?),
-        )),
+        Number::Integer(i) => {
+            stack.push(RuntimeValue::Number(Number::Natural(
+                i.try_into().map_err(|_| invalid(&Number::Integer(i)))?,
+            )))
+        }
+        Number::BigInt(b) => stack.push(RuntimeValue::Number(Number::Natural(
+            b.to_u64().ok_or_else(|| invalid(&Number::BigInt(b)))?,
+        ))),
+        Number::Rational(r) => {
+            stack.push(RuntimeValue::Number(Number::Natural(
+                truncate(&r)
+                    .to_u64()
+                    .ok_or_else(|| invalid(&Number::Rational(r)))?,
+            )))
+        }
         Number::Float(f) => {
             stack.push(RuntimeValue::Number(Number::Natural(f as u64)))
         }
@@ -25,17 +58,30 @@ pub fn apply_natural(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
 }
 
 pub fn apply_integer(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    let element = runtime_error::ensure_number(stack)?;
+    let element = ensure_number_or_string(stack)?;
+    let invalid = |n: &Number| {
+        format!("Conversion from {} to integer is invalid", n.type_fmt())
+    };
 
     match element {
-        Number::Natural(n) => stack.push(RuntimeValue::Number(
-            Number::Integer(n.try_into().map_err(|_| {
-                format!("Conversion from natural '{}' to integer is invalid", n)
-            })?),
-        )),
+        Number::Natural(n) => {
+            stack.push(RuntimeValue::Number(Number::Integer(
+                n.try_into().map_err(|_| invalid(&Number::Natural(n)))?,
+            )))
+        }
         Number::Integer(i) => {
             stack.push(RuntimeValue::Number(Number::Integer(i)))
         }
+        Number::BigInt(b) => stack.push(RuntimeValue::Number(Number::Integer(
+            b.to_i64().ok_or_else(|| invalid(&Number::BigInt(b)))?,
+        ))),
+        Number::Rational(r) => {
+            stack.push(RuntimeValue::Number(Number::Integer(
+                truncate(&r)
+                    .to_i64()
+                    .ok_or_else(|| invalid(&Number::Rational(r)))?,
+            )))
+        }
         Number::Float(f) => {
             stack.push(RuntimeValue::Number(Number::Integer(f as i64)))
         }
@@ -44,8 +90,46 @@ pub fn apply_integer(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
     Ok(())
 }
 
+pub fn apply_bigint(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let element = ensure_number_or_string(stack)?;
+
+    let bigint = match element {
+        Number::Natural(n) => BigInt::from(n),
+        Number::Integer(i) => BigInt::from(i),
+        Number::BigInt(b) => b,
+        Number::Rational(r) => truncate(&r),
+        Number::Float(f) => {
+            Rational::from_f64(f).map(|r| truncate(&r)).ok_or_else(|| {
+                format!("Conversion from float '{}' to bigint is invalid", f)
+            })?
+        }
+    };
+
+    stack.push(RuntimeValue::Number(Number::BigInt(bigint)));
+
+    Ok(())
+}
+
+pub fn apply_rational(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let element = ensure_number_or_string(stack)?;
+
+    let rational = match element {
+        Number::Natural(n) => Rational::from(BigInt::from(n)),
+        Number::Integer(i) => Rational::from(BigInt::from(i)),
+        Number::BigInt(b) => Rational::from(b),
+        Number::Rational(r) => r,
+        Number::Float(f) => Rational::from_f64(f).ok_or_else(|| {
+            format!("Conversion from float '{}' to rational is invalid", f)
+        })?,
+    };
+
+    stack.push(RuntimeValue::Number(Number::Rational(rational)));
+
+    Ok(())
+}
+
 pub fn apply_float(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    let element = runtime_error::ensure_number(stack)?;
+    let element = ensure_number_or_string(stack)?;
 
     match element {
         Number::Natural(n) => {
@@ -54,8 +138,48 @@ pub fn apply_float(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
         Number::Integer(i) => {
             stack.push(RuntimeValue::Number(Number::Float(i as f64)))
         }
+        Number::BigInt(b) => {
+            stack.push(RuntimeValue::Number(Number::Float(b.to_f64())))
+        }
+        Number::Rational(r) => {
+            stack.push(RuntimeValue::Number(Number::Float(r.to_f64())))
+        }
         Number::Float(f) => stack.push(RuntimeValue::Number(Number::Float(f))),
     }
 
     Ok(())
 }
+
+// NOTE: Rationals are rounded towards zero like floats.
+fn truncate(r: &Rational) -> BigInt {
+    r.trunc().numer().clone()
+}
+
+pub fn apply_boolean(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let boolean = match runtime_error::ensure_element(stack)? {
+        RuntimeValue::Boolean(b) => b,
+        RuntimeValue::String(s) => match s.trim() {
+            "true" => true,
+            "false" => false,
+            s => return Err(format!("'{}' isn't a boolean", s)),
+        },
+        v => {
+            return Err(format!(
+                "Expected boolean or string found {}",
+                v.type_fmt()
+            ))
+        }
+    };
+
+    stack.push(RuntimeValue::Boolean(boolean));
+
+    Ok(())
+}
+
+pub fn apply_tostring(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let value = runtime_error::ensure_element(stack)?;
+
+    stack.push(RuntimeValue::String(value.to_string()));
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use super::runtime_error;
+use super::runtime_value::RuntimeValue;
+use crate::lex::Number;
+
+use std::env;
+
+pub fn apply_argc(
+    stack: &mut Vec<RuntimeValue>,
+    args: &[String],
+) -> Result<(), String> {
+    stack.push(RuntimeValue::Number(Number::Natural(args.len() as u64)));
+    Ok(())
+}
+
+pub fn apply_argv(
+    stack: &mut Vec<RuntimeValue>,
+    args: &[String],
+) -> Result<(), String> {
+    let index = runtime_error::ensure_element(stack)?;
+    let index = match index {
+        RuntimeValue::Number(Number::Natural(n)) => n,
+        _ => {
+            return Err(format!(
+                "Can't use {} as argument index",
+                index.type_fmt()
+            ))
+        }
+    };
+
+    match args.get(index as usize) {
+        Some(arg) => {
+            stack.push(RuntimeValue::String(arg.clone()));
+            Ok(())
+        }
+        None => Err(format!(
+            "Invalid index {} for {} argument(s)",
+            index,
+            args.len()
+        )),
+    }
+}
+
+// NOTE: An unset variable results in an empty string and false, so that the
+// stack effect is always the same.
+pub fn apply_getenv(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let name = runtime_error::ensure_string(stack)?;
+
+    match env::var_os(&name) {
+        Some(value) => {
+            stack.push(RuntimeValue::String(
+                value.to_string_lossy().into_owned(),
+            ));
+            stack.push(RuntimeValue::Boolean(true));
+        }
+        None => {
+            stack.push(RuntimeValue::String(String::new()));
+            stack.push(RuntimeValue::Boolean(false));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
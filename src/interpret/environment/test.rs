@@ -0,0 +1,58 @@
+use super::*;
+
+fn args() -> Vec<String> {
+    vec!["first".to_owned(), "second".to_owned()]
+}
+
+#[test]
+fn test_argc() {
+    let stack = &mut vec![];
+
+    assert_eq!(apply_argc(stack, &args()), Ok(()));
+    assert_eq!(stack, &[RuntimeValue::Number(Number::Natural(2))]);
+}
+
+#[test]
+fn test_argv() {
+    let stack = &mut vec![RuntimeValue::Number(Number::Natural(1))];
+
+    assert_eq!(apply_argv(stack, &args()), Ok(()));
+    assert_eq!(stack, &[RuntimeValue::String("second".to_owned())]);
+
+    let stack = &mut vec![RuntimeValue::Number(Number::Natural(2))];
+    assert_eq!(
+        apply_argv(stack, &args()),
+        Err("Invalid index 2 for 2 argument(s)".to_owned())
+    );
+
+    let stack = &mut vec![RuntimeValue::String("0".to_owned())];
+    assert_eq!(
+        apply_argv(stack, &args()),
+        Err("Can't use string '0' as argument index".to_owned())
+    );
+}
+
+#[test]
+fn test_getenv() {
+    env::set_var("PILE_TEST_GETENV", "value");
+
+    let stack = &mut vec![RuntimeValue::String("PILE_TEST_GETENV".to_owned())];
+    assert_eq!(apply_getenv(stack), Ok(()));
+    assert_eq!(
+        stack,
+        &[
+            RuntimeValue::String("value".to_owned()),
+            RuntimeValue::Boolean(true)
+        ]
+    );
+
+    let stack = &mut vec![RuntimeValue::String("PILE_TEST_UNSET".to_owned())];
+    assert_eq!(apply_getenv(stack), Ok(()));
+    assert_eq!(
+        stack,
+        &[
+            RuntimeValue::String(String::new()),
+            RuntimeValue::Boolean(false)
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+use super::output::Output;
+use super::runtime_error;
+use super::runtime_value::RuntimeValue;
+use crate::lex::Number;
+
+use std::convert::TryFrom;
+
+pub fn exit_status(value: Option<&RuntimeValue>) -> Result<i32, String> {
+    match value {
+        Some(RuntimeValue::Number(Number::Natural(n))) => u8::try_from(*n)
+            .map(i32::from)
+            .map_err(|_| format!("Exit status {} isn't between 0 and 255", n)),
+        Some(value) => Err(format!(
+            "Expected natural as exit status found {}",
+            value.type_fmt()
+        )),
+        None => {
+            Err("Expected natural as exit status found empty stack".to_owned())
+        }
+    }
+}
+
+// NOTE: Only checks the status and flushes the output. The interpreter ends
+// the program with an error that carries the status, so the caller decides how
+// to exit.
+pub fn apply_exit(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+) -> Result<i32, String> {
+    let value = runtime_error::ensure_element(stack)?;
+    let status = exit_status(Some(&value))?;
+
+    output.flush()?;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,31 @@
+use super::*;
+
+#[test]
+fn test_exit_status() {
+    assert_eq!(
+        exit_status(Some(&RuntimeValue::Number(Number::Natural(3)))),
+        Ok(3)
+    );
+    assert_eq!(
+        exit_status(Some(&RuntimeValue::Number(Number::Natural(256)))),
+        Err("Exit status 256 isn't between 0 and 255".to_owned())
+    );
+    assert_eq!(
+        exit_status(Some(&RuntimeValue::Number(Number::Integer(-1)))),
+        Err("Expected natural as exit status found integer '-1'".to_owned())
+    );
+    assert_eq!(
+        exit_status(None),
+        Err("Expected natural as exit status found empty stack".to_owned())
+    );
+}
+
+#[test]
+fn test_exit_underflow() {
+    let (mut output, _, _) = crate::interpret::output::test::buffered();
+
+    assert_eq!(
+        apply_exit(&mut vec![], &mut output),
+        Err("Stack underflow".to_owned())
+    );
+}
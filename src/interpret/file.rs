@@ -0,0 +1,44 @@
+use super::reader::InputMode;
+use super::runtime_error;
+use super::runtime_value::RuntimeValue;
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+
+fn os_error(path: &str, err: std::io::Error) -> String {
+    format!("{}: {}", path, err)
+}
+
+pub fn apply_readfile(
+    stack: &mut Vec<RuntimeValue>,
+    mode: InputMode,
+) -> Result<(), String> {
+    let path = runtime_error::ensure_string(stack)?;
+    let mut file = File::open(&path).map_err(|err| os_error(&path, err))?;
+    let content = mode.read_all(&mut file, &path)?;
+
+    stack.push(RuntimeValue::String(content));
+    Ok(())
+}
+
+pub fn apply_writefile(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let path = runtime_error::ensure_string(stack)?;
+    let content = runtime_error::ensure_string(stack)?;
+
+    fs::write(&path, content).map_err(|err| os_error(&path, err))
+}
+
+pub fn apply_appendfile(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let path = runtime_error::ensure_string(stack)?;
+    let content = runtime_error::ensure_string(stack)?;
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|err| os_error(&path, err))
+}
+
+#[cfg(test)]
+mod test;
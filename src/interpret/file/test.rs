@@ -0,0 +1,107 @@
+use super::*;
+
+use std::path::PathBuf;
+
+fn temp_file(name: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!(
+        "pile-{}-{}",
+        std::process::id(),
+        name
+    ));
+    let _ = fs::remove_file(&path);
+    path.to_string_lossy().into_owned()
+}
+
+fn string(s: &str) -> RuntimeValue {
+    RuntimeValue::String(s.to_owned())
+}
+
+#[test]
+fn test_write_append_read() {
+    let path = temp_file("write_append_read");
+
+    let stack = &mut vec![string("first\n"), string(&path)];
+    assert_eq!(apply_writefile(stack), Ok(()));
+    assert!(stack.is_empty());
+
+    let stack = &mut vec![string("second\n"), string(&path)];
+    assert_eq!(apply_appendfile(stack), Ok(()));
+
+    let stack = &mut vec![string(&path)];
+    assert_eq!(apply_readfile(stack, InputMode::default()), Ok(()));
+    assert_eq!(stack, &[string("first\nsecond\n")]);
+
+    let stack = &mut vec![string("third\n"), string(&path)];
+    assert_eq!(apply_writefile(stack), Ok(()));
+
+    let stack = &mut vec![string(&path)];
+    assert_eq!(apply_readfile(stack, InputMode::default()), Ok(()));
+    assert_eq!(stack, &[string("third\n")]);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_append_creates_file() {
+    let path = temp_file("append_creates_file");
+
+    let stack = &mut vec![string("text"), string(&path)];
+    assert_eq!(apply_appendfile(stack), Ok(()));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "text");
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_readfile_input_mode() {
+    let path = temp_file("readfile_input_mode");
+    fs::write(&path, b"a\xffb\r\nc\r\n").unwrap();
+
+    let stack = &mut vec![string(&path)];
+    assert_eq!(
+        apply_readfile(stack, InputMode::default()),
+        Err(format!("{}: Invalid UTF-8 in input", path))
+    );
+
+    let lossy = InputMode {
+        lossy: true,
+        keep_cr: false,
+    };
+    let stack = &mut vec![string(&path)];
+    assert_eq!(apply_readfile(stack, lossy), Ok(()));
+    assert_eq!(stack, &[string("a\u{fffd}b\nc\n")]);
+
+    let keep_cr = InputMode {
+        lossy: true,
+        keep_cr: true,
+    };
+    let stack = &mut vec![string(&path)];
+    assert_eq!(apply_readfile(stack, keep_cr), Ok(()));
+    assert_eq!(stack, &[string("a\u{fffd}b\r\nc\r\n")]);
+
+    let _ = fs::remove_file(&path);
+}
+
+#[test]
+fn test_file_errors() {
+    let path = temp_file("missing");
+
+    let stack = &mut vec![string(&path)];
+    assert_eq!(
+        apply_readfile(stack, InputMode::default()),
+        Err(format!("{}: No such file or directory (os error 2)", path))
+    );
+
+    let dir = std::env::temp_dir().to_string_lossy().into_owned();
+    let stack = &mut vec![string("text"), string(&dir)];
+    assert_eq!(
+        apply_writefile(stack),
+        Err(format!("{}: Is a directory (os error 21)", dir))
+    );
+
+    let stack = &mut vec![RuntimeValue::Boolean(true), string(&path)];
+    assert_eq!(
+        apply_writefile(stack),
+        Err("Expected string found boolean 'true'".to_owned())
+    );
+}
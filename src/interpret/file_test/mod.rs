@@ -24,7 +24,7 @@ fn expect_stack(filename: &str, expected: &[RuntimeValue]) {
     );
     let parser = Parser::new(lexer);
     let ast = locals::translate(parser.parse().expect("invalid program"));
-    let ast = using::resolve(ast).expect("invalid 'use'");
+    let ast = using::resolve(ast, &[]).expect("invalid 'use'");
 
     let mut interpreter = Interpreter::new(ast, 10, false);
 
@@ -44,7 +44,7 @@ fn expect_error(filename: &str, expected: PileError) {
     );
     let parser = Parser::new(lexer);
     let ast = locals::translate(parser.parse().expect("invalid program"));
-    let ast = using::resolve(ast).expect("invalid 'use'");
+    let ast = using::resolve(ast, &[]).expect("invalid 'use'");
 
     let mut interpreter = Interpreter::new(ast, 10, false);
 
@@ -128,3 +128,132 @@ fn proj_error_eval() {
         ),
     )
 }
+
+#[test]
+fn proj_namespace() {
+    expect_stack(
+        &test_file("proj_namespace/main.pile"),
+        &[
+            RuntimeValue::Number(Number::Natural(10)),
+            RuntimeValue::Number(Number::Natural(15)),
+            RuntimeValue::Number(Number::Natural(9)),
+            RuntimeValue::Number(Number::Natural(20)),
+        ],
+    )
+}
+
+#[test]
+fn proj_shared_alias() {
+    expect_stack(
+        &test_file("proj_shared_alias/main.pile"),
+        &[
+            RuntimeValue::Number(Number::Natural(99)),
+            RuntimeValue::Number(Number::Natural(99)),
+        ],
+    )
+}
+
+#[test]
+fn proj_diamond() {
+    expect_stack(
+        &test_file("proj_diamond/main.pile"),
+        &[
+            RuntimeValue::String("util loaded".to_owned()),
+            RuntimeValue::Number(Number::Natural(4)),
+            RuntimeValue::Number(Number::Natural(6)),
+        ],
+    )
+}
+
+#[test]
+fn proj_conditional_use() {
+    expect_stack(
+        &test_file("proj_conditional_use/main.pile"),
+        &[
+            RuntimeValue::Number(Number::Natural(0)),
+            RuntimeValue::String("plugin loaded".to_owned()),
+            RuntimeValue::Number(Number::Natural(42)),
+            RuntimeValue::Number(Number::Natural(42)),
+            RuntimeValue::Number(Number::Natural(42)),
+        ],
+    )
+}
+
+#[test]
+fn proj_load() {
+    expect_stack(
+        &test_file("proj_load/main.pile"),
+        &[
+            RuntimeValue::String("fast loaded".to_owned()),
+            RuntimeValue::Number(Number::Natural(20)),
+            RuntimeValue::Number(Number::Natural(10)),
+        ],
+    )
+}
+
+#[test]
+fn proj_load_shared() {
+    expect_stack(
+        &test_file("proj_load_shared/main.pile"),
+        &[
+            RuntimeValue::String("util loaded".to_owned()),
+            RuntimeValue::Number(Number::Natural(6)),
+            RuntimeValue::Number(Number::Natural(9)),
+        ],
+    )
+}
+
+#[test]
+fn proj_load_errors() {
+    let missing_file = test_file("proj_load/missing.pile");
+    expect_error(
+        &missing_file,
+        PileError::in_line(
+            Rc::new(ProgramSource::File(PathBuf::from(&missing_file))),
+            2,
+            format!(
+                "Couldn't find 'strategies/slow'. Tried the following \
+                 locations:\n    {}",
+                test_file("proj_load/strategies/slow.pile")
+            ),
+        ),
+    );
+
+    let broken_file = test_file("proj_load/broken.pile");
+    expect_error(
+        &broken_file,
+        PileError::in_line(
+            Rc::new(ProgramSource::File(PathBuf::from(&broken_file))),
+            1,
+            format!(
+                "{}:2: Expected 'end' found end of file.",
+                test_file("proj_load/strategies/broken.pile")
+            ),
+        ),
+    );
+}
+
+#[test]
+fn proj_readfilelines() {
+    expect_stack(
+        &test_file("proj_readfilelines/main.pile"),
+        &[
+            RuntimeValue::String("first".to_owned()),
+            RuntimeValue::String("second".to_owned()),
+            RuntimeValue::String("stop".to_owned()),
+        ],
+    )
+}
+
+#[test]
+fn proj_transitive() {
+    expect_stack(
+        &test_file("proj_transitive/main.pile"),
+        &[
+            RuntimeValue::String("base loaded".to_owned()),
+            RuntimeValue::Number(Number::Natural(10)),
+            RuntimeValue::Number(Number::Natural(11)),
+            RuntimeValue::Number(Number::Natural(10)),
+        ],
+    )
+}
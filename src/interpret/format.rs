@@ -0,0 +1,343 @@
+use super::runtime_error;
+use super::runtime_value::RuntimeValue;
+use crate::lex::Number;
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Display,
+    LowerHex,
+    UpperHex,
+    Binary,
+    Octal,
+}
+
+#[derive(Debug, PartialEq)]
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Kind,
+}
+
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Literal(String),
+    Value(usize, Spec),
+}
+
+// NOTE: The values are taken from the stack in order, so '{0}' is the
+// deepest one. Like in Rust, '{}' counts independently of explicit
+// positions and every value up to the highest one used is consumed.
+pub fn apply_format(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let format = runtime_error::ensure_string(stack)?;
+    let pieces = parse(&format)?;
+    let count = pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Value(index, _) => index + 1,
+            Piece::Literal(_) => 0,
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut values = vec![];
+    for _ in 0..count {
+        values.push(runtime_error::ensure_element(stack)?);
+    }
+    values.reverse();
+
+    let mut result = String::new();
+    for piece in &pieces {
+        match piece {
+            Piece::Literal(text) => result += text,
+            Piece::Value(index, spec) => {
+                result += &render(&values[*index], spec)?
+            }
+        }
+    }
+
+    stack.push(RuntimeValue::String(result));
+
+    Ok(())
+}
+
+fn parse(format: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = vec![];
+    let mut literal = String::new();
+    let mut next_index = 0;
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut content = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => {
+                            return Err(format!(
+                                "Unmatched '{{' in format \"{}\"",
+                                format
+                            ))
+                        }
+                        Some(c) => content.push(c),
+                    }
+                }
+
+                let (index, spec) = placeholder(&content).map_err(|msg| {
+                    format!("Invalid placeholder '{{{}}}': {}", content, msg)
+                })?;
+                let index = index.unwrap_or_else(|| {
+                    next_index += 1;
+                    next_index - 1
+                });
+
+                pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                pieces.push(Piece::Value(index, spec));
+            }
+            '}' => {
+                return Err(format!("Unmatched '}}' in format \"{}\"", format))
+            }
+            c => literal.push(c),
+        }
+    }
+
+    pieces.push(Piece::Literal(literal));
+
+    Ok(pieces)
+}
+
+fn placeholder(text: &str) -> Result<(Option<usize>, Spec), String> {
+    let (index, spec) = match text.split_once(':') {
+        Some((index, spec)) => (index, spec),
+        None => (text, ""),
+    };
+    let index =
+        if index.is_empty() {
+            None
+        } else {
+            Some(index.parse().map_err(|_| {
+                format!("expected a position found '{}'", index)
+            })?)
+        };
+
+    Ok((index, spec_of(spec)?))
+}
+
+fn spec_of(text: &str) -> Result<Spec, String> {
+    let mut spec = Spec {
+        fill: ' ',
+        align: None,
+        zero: false,
+        width: 0,
+        precision: None,
+        kind: Kind::Display,
+    };
+    let mut chars = text.chars().peekable();
+
+    let mut ahead = text.chars();
+    match (ahead.next(), ahead.next().and_then(align_of)) {
+        (Some(fill), Some(align)) => {
+            spec.fill = fill;
+            spec.align = Some(align);
+            chars.next();
+            chars.next();
+        }
+        (Some(c), None) if align_of(c).is_some() => {
+            spec.align = align_of(c);
+            chars.next();
+        }
+        _ => (),
+    }
+
+    if chars.next_if_eq(&'0').is_some() {
+        spec.zero = true;
+    }
+
+    spec.width = number(&mut chars)?.unwrap_or(0);
+
+    if chars.next_if_eq(&'.').is_some() {
+        spec.precision = Some(
+            number(&mut chars)?
+                .ok_or_else(|| "expected a precision after '.'".to_owned())?,
+        );
+    }
+
+    spec.kind = match chars.next() {
+        None => Kind::Display,
+        Some('x') => Kind::LowerHex,
+        Some('X') => Kind::UpperHex,
+        Some('b') => Kind::Binary,
+        Some('o') => Kind::Octal,
+        Some(c) => return Err(format!("unknown type '{}'", c)),
+    };
+
+    match chars.next() {
+        None => Ok(spec),
+        Some(c) => Err(format!("unexpected '{}' after the type", c)),
+    }
+}
+
+fn align_of(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        _ => None,
+    }
+}
+
+fn number(chars: &mut Peekable<Chars>) -> Result<Option<usize>, String> {
+    let mut digits = String::new();
+
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        digits.push(digit);
+    }
+
+    if digits.is_empty() {
+        Ok(None)
+    } else {
+        digits
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("'{}' is too large", digits))
+    }
+}
+
+fn render(value: &RuntimeValue, spec: &Spec) -> Result<String, String> {
+    let text = match (spec.kind, spec.precision, value) {
+        (Kind::Display, None, value) => value.to_string(),
+        (
+            Kind::Display,
+            Some(precision),
+            RuntimeValue::Number(Number::Float(f)),
+        ) => format!("{:.*}", precision, f),
+        (Kind::Display, Some(precision), RuntimeValue::String(s)) => {
+            s.chars().take(precision).collect()
+        }
+        (Kind::Display, Some(_), value) => {
+            return Err(format!(
+                "Can't use a precision with {}",
+                value.type_fmt()
+            ))
+        }
+        (kind, Some(_), _) => {
+            return Err(format!(
+                "Can't use a precision with {}",
+                kind_name(kind)
+            ))
+        }
+        (kind, None, RuntimeValue::Number(Number::Natural(n))) => {
+            radix(*n, kind)
+        }
+        (kind, None, RuntimeValue::Number(Number::Integer(i))) => {
+            let sign = if *i < 0 { "-" } else { "" };
+            sign.to_owned() + &radix(i.unsigned_abs(), kind)
+        }
+        (kind, None, RuntimeValue::Number(Number::BigInt(b))) => {
+            let digits = b.to_str_radix(base(kind));
+            if kind == Kind::UpperHex {
+                digits.to_uppercase()
+            } else {
+                digits
+            }
+        }
+        (kind, None, value) => {
+            return Err(format!(
+                "Can't format {} as {}",
+                value.type_fmt(),
+                kind_name(kind)
+            ))
+        }
+    };
+
+    let is_number = matches!(value, RuntimeValue::Number(_));
+    if spec.zero && !is_number {
+        return Err(format!("Can't pad {} with zeros", value.type_fmt()));
+    }
+
+    Ok(pad(text, spec, is_number))
+}
+
+fn radix(n: u64, kind: Kind) -> String {
+    match kind {
+        Kind::Display => n.to_string(),
+        Kind::LowerHex => format!("{:x}", n),
+        Kind::UpperHex => format!("{:X}", n),
+        Kind::Binary => format!("{:b}", n),
+        Kind::Octal => format!("{:o}", n),
+    }
+}
+
+fn base(kind: Kind) -> u32 {
+    match kind {
+        Kind::Display => 10,
+        Kind::LowerHex | Kind::UpperHex => 16,
+        Kind::Binary => 2,
+        Kind::Octal => 8,
+    }
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Display => "text",
+        Kind::LowerHex | Kind::UpperHex => "hex",
+        Kind::Binary => "binary",
+        Kind::Octal => "octal",
+    }
+}
+
+// NOTE: Zero padding goes between the sign and the digits and overrides the
+// alignment, like in Rust.
+fn pad(text: String, spec: &Spec, is_number: bool) -> String {
+    let length = text.chars().count();
+    if length >= spec.width {
+        return text;
+    }
+    let missing = spec.width - length;
+
+    if spec.zero {
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        return format!("{}{}{}", sign, "0".repeat(missing), digits);
+    }
+
+    let align = spec.align.unwrap_or(if is_number {
+        Align::Right
+    } else {
+        Align::Left
+    });
+    let (before, after) = match align {
+        Align::Left => (0, missing),
+        Align::Right => (missing, 0),
+        Align::Center => (missing / 2, missing - missing / 2),
+    };
+    let fill = |n| spec.fill.to_string().repeat(n);
+
+    fill(before) + &text + &fill(after)
+}
+
+#[cfg(test)]
+mod test;
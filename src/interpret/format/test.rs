@@ -0,0 +1,184 @@
+use proptest::prelude::*;
+
+use super::*;
+
+fn runtime_value_strategy() -> impl Strategy<Value = RuntimeValue> {
+    prop_oneof![
+        any::<u64>().prop_map(|n| RuntimeValue::Number(Number::Natural(n))),
+        any::<f64>().prop_map(|f| RuntimeValue::Number(Number::Float(f))),
+        any::<i64>().prop_map(|i| RuntimeValue::Number(Number::Integer(i))),
+        any::<bool>().prop_map(RuntimeValue::Boolean),
+        ".*".prop_map(RuntimeValue::String),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_one_arg_format(v in runtime_value_strategy(),
+                           f in r#"[^{}]*\{\}[^{}]*"#) {
+        let stack = &mut vec![v.clone(), RuntimeValue::String(f)];
+        apply_format(stack).unwrap();
+
+        prop_assert!(stack.len() == 1);
+        match &stack[0] {
+            RuntimeValue::String(s) => prop_assert!(s.contains(&v.to_string())),
+            _ => prop_assert!(false),
+        }
+    }
+
+    #[test]
+    fn test_two_arg_format(v1 in runtime_value_strategy(),
+                           v2 in runtime_value_strategy(),
+                           f in r#"[^{}]*\{\}[^{}]*\{\}[^{}]*"#) {
+        let stack = &mut vec![v1.clone(), v2.clone(), RuntimeValue::String(f)];
+        apply_format(stack).unwrap();
+
+        prop_assert_eq!(stack.len(), 1);
+        match &stack[0] {
+            RuntimeValue::String(s) => {
+                prop_assert!(s.contains(&v1.to_string()));
+                prop_assert!(s.contains(&v2.to_string()));
+            }
+            _ => prop_assert!(false),
+        }
+    }
+}
+
+fn format(values: Vec<RuntimeValue>, format: &str) -> Result<String, String> {
+    let mut stack = values;
+    stack.push(RuntimeValue::String(format.to_owned()));
+    apply_format(&mut stack)?;
+
+    match stack.pop() {
+        Some(RuntimeValue::String(s)) if stack.is_empty() => Ok(s),
+        value => panic!("unexpected result {:?} {:?}", value, stack),
+    }
+}
+
+fn natural(n: u64) -> RuntimeValue {
+    RuntimeValue::Number(Number::Natural(n))
+}
+
+fn integer(i: i64) -> RuntimeValue {
+    RuntimeValue::Number(Number::Integer(i))
+}
+
+fn float(f: f64) -> RuntimeValue {
+    RuntimeValue::Number(Number::Float(f))
+}
+
+fn bigint(s: &str) -> RuntimeValue {
+    RuntimeValue::Number(Number::BigInt(s.parse().unwrap()))
+}
+
+fn string(s: &str) -> RuntimeValue {
+    RuntimeValue::String(s.to_owned())
+}
+
+#[test]
+fn test_format_alignment() {
+    assert_eq!(format(vec![natural(42)], "[{:>6}]"), Ok("[    42]".into()));
+    assert_eq!(format(vec![natural(42)], "[{:<6}]"), Ok("[42    ]".into()));
+    assert_eq!(format(vec![natural(42)], "[{:^6}]"), Ok("[  42  ]".into()));
+    assert_eq!(format(vec![natural(42)], "[{:6}]"), Ok("[    42]".into()));
+    assert_eq!(format(vec![string("äb")], "[{:5}]"), Ok("[äb   ]".into()));
+    assert_eq!(format(vec![string("ab")], "[{:*^5}]"), Ok("[*ab**]".into()));
+    assert_eq!(format(vec![string("long")], "[{:2}]"), Ok("[long]".into()));
+}
+
+#[test]
+fn test_format_numbers() {
+    assert_eq!(format(vec![float(1.23456)], "{:.3}"), Ok("1.235".into()));
+    assert_eq!(format(vec![float(2.5)], "{:>8.2}"), Ok("    2.50".into()));
+    assert_eq!(format(vec![natural(42)], "{:08}"), Ok("00000042".into()));
+    assert_eq!(format(vec![integer(-42)], "{:06}"), Ok("-00042".into()));
+    assert_eq!(format(vec![float(-1.5)], "{:07.2}"), Ok("-001.50".into()));
+    assert_eq!(format(vec![natural(255)], "{:x}"), Ok("ff".into()));
+    assert_eq!(format(vec![natural(255)], "{:X}"), Ok("FF".into()));
+    assert_eq!(format(vec![natural(5)], "{:08b}"), Ok("00000101".into()));
+    assert_eq!(format(vec![natural(8)], "{:o}"), Ok("10".into()));
+    assert_eq!(format(vec![integer(-255)], "{:x}"), Ok("-ff".into()));
+    assert_eq!(format(vec![string("abcdef")], "{:.3}"), Ok("abc".into()));
+}
+
+#[test]
+fn test_format_bigints() {
+    let big = bigint("-340282366920938463463374607431768211455");
+
+    assert_eq!(
+        format(vec![big.clone()], "{:X}"),
+        Ok("-FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF".into())
+    );
+    assert_eq!(format(vec![bigint("5")], "{:08b}"), Ok("00000101".into()));
+    assert_eq!(
+        format(vec![big], "{:.2}"),
+        Err("Can't use a precision with bigint \
+             '-340282366920938463463374607431768211455'"
+            .into())
+    );
+}
+
+#[test]
+fn test_format_positions() {
+    assert_eq!(
+        format(vec![string("a"), string("b")], "{1}{0}{1}"),
+        Ok("bab".into())
+    );
+    assert_eq!(
+        format(vec![natural(1), natural(2)], "{} {0} {}"),
+        Ok("1 1 2".into())
+    );
+    assert_eq!(
+        format(vec![natural(1), natural(2)], "{1:>3}|{0:<3}|"),
+        Ok("  2|1  |".into())
+    );
+    assert_eq!(format(vec![natural(7)], "{{{}}}"), Ok("{7}".into()));
+    assert_eq!(format(vec![], "{{}}"), Ok("{}".into()));
+}
+
+#[test]
+fn test_format_errors() {
+    let error = |values, f: &str| format(values, f).unwrap_err();
+
+    assert_eq!(
+        error(vec![natural(1)], "{:q}"),
+        "Invalid placeholder '{:q}': unknown type 'q'"
+    );
+    assert_eq!(
+        error(vec![natural(1)], "{:.}"),
+        "Invalid placeholder '{:.}': expected a precision after '.'"
+    );
+    assert_eq!(
+        error(vec![natural(1)], "{a}"),
+        "Invalid placeholder '{a}': expected a position found 'a'"
+    );
+    assert_eq!(
+        error(vec![natural(1)], "{:x5}"),
+        "Invalid placeholder '{:x5}': unexpected '5' after the type"
+    );
+    assert_eq!(
+        error(vec![natural(1)], "{"),
+        "Unmatched '{' in format \"{\""
+    );
+    assert_eq!(
+        error(vec![natural(1)], "a } b"),
+        "Unmatched '}' in format \"a } b\""
+    );
+    assert_eq!(error(vec![natural(1)], "{1}"), "Stack underflow");
+    assert_eq!(
+        error(vec![natural(1)], "{:.2}"),
+        "Can't use a precision with natural '1'"
+    );
+    assert_eq!(
+        error(vec![natural(1)], "{:.2x}"),
+        "Can't use a precision with hex"
+    );
+    assert_eq!(
+        error(vec![float(1.5)], "{:b}"),
+        "Can't format float '1.5' as binary"
+    );
+    assert_eq!(
+        error(vec![string("a")], "{:05}"),
+        "Can't pad string 'a' with zeros"
+    );
+}
@@ -0,0 +1,37 @@
+use super::runtime_error;
+use super::Interpreter;
+use super::State;
+use crate::pile_error::PileError;
+use crate::program_source::ProgramSource;
+use crate::using;
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+pub fn apply_load(
+    state: &mut State,
+    source: &Rc<ProgramSource>,
+) -> Result<(), PileError> {
+    let lines = state.current_lines;
+    let to_pile_error =
+        |msg| PileError::in_range(Rc::clone(source), lines, msg);
+    let path = runtime_error::ensure_string(&mut state.stack)
+        .map_err(to_pile_error)?;
+
+    let current_dir = match source.as_ref() {
+        ProgramSource::File(file) => using::directory_of(file),
+        ProgramSource::Repl | ProgramSource::Stdin | ProgramSource::Inline => {
+            PathBuf::from(".")
+        }
+    };
+
+    let expressions = using::load_module(
+        Path::new(&path),
+        &current_dir,
+        &state.search_path,
+        &mut state.module_cache,
+    )
+    .map_err(to_pile_error)?;
+
+    Interpreter::call(&expressions, state, source)
+}
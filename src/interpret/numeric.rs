@@ -1,47 +1,216 @@
 use super::runtime_error;
 use super::runtime_value::RuntimeValue;
-use crate::lex::Number;
+use crate::lex::{BigInt, Number, Rational};
 
-fn apply_numeric<N, I, F>(
-    op_natural: N,
-    op_integer: I,
-    op_float: F,
-    stack: &mut Vec<RuntimeValue>,
-) -> Result<(), String>
-where
-    N: Fn(&u64, &u64) -> Result<u64, String>,
-    I: Fn(&i64, &i64) -> Result<i64, String>,
-    F: Fn(&f64, &f64) -> f64,
-{
-    let right = runtime_error::ensure_element(stack)?;
-    let left = runtime_error::ensure_element(stack)?;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::mem;
+
+// NOTE: Mixed numbers are promoted along natural → integer → bigint →
+// rational → float. A natural that doesn't fit into an integer becomes a
+// bigint and a conversion to float that would change the value is an error.
+pub fn promote(
+    left: &Number,
+    right: &Number,
+) -> Result<(Number, Number), String> {
+    match (left, right) {
+        _ if mem::discriminant(left) == mem::discriminant(right) => {
+            Ok((left.clone(), right.clone()))
+        }
+        (Number::Float(_), _) | (_, Number::Float(_)) => Ok((
+            Number::Float(exact_float(left)?),
+            Number::Float(exact_float(right)?),
+        )),
+        (Number::Rational(_), _) | (_, Number::Rational(_)) => Ok((
+            Number::Rational(exact_rational(left)?),
+            Number::Rational(exact_rational(right)?),
+        )),
+        (Number::BigInt(_), _) | (_, Number::BigInt(_)) => Ok((
+            Number::BigInt(exact_bigint(left)?),
+            Number::BigInt(exact_bigint(right)?),
+        )),
+        _ => match (exact_integer(left), exact_integer(right)) {
+            (Ok(left), Ok(right)) => {
+                Ok((Number::Integer(left), Number::Integer(right)))
+            }
+            _ => Ok((
+                Number::BigInt(exact_bigint(left)?),
+                Number::BigInt(exact_bigint(right)?),
+            )),
+        },
+    }
+}
+
+// NOTE: Floats are compared with the exact value of the other number, so
+// numbers beyond 2^53 aren't rounded. Infinities lie beyond every number and
+// NaN can't be compared.
+pub fn compare(left: &Number, right: &Number) -> Option<Ordering> {
+    let compare_float = |f: f64, n: &Number| {
+        let n = exact_rational(n).ok()?;
+        match Rational::from_f64(f) {
+            Some(f) => Some(f.cmp(&n)),
+            None if f.is_nan() => None,
+            None if f > 0.0 => Some(Ordering::Greater),
+            None => Some(Ordering::Less),
+        }
+    };
+
+    match (left, right) {
+        (Number::Float(_), Number::Float(_)) => (),
+        (Number::Float(f), n) => return compare_float(*f, n),
+        (n, Number::Float(f)) => {
+            return compare_float(*f, n).map(Ordering::reverse)
+        }
+        _ => (),
+    }
+
+    let (left, right) = promote(left, right).ok()?;
+    RuntimeValue::Number(left).partial_cmp(&RuntimeValue::Number(right))
+}
+
+fn exact_integer(n: &Number) -> Result<i64, String> {
+    match n {
+        Number::Natural(n) => i64::try_from(*n)
+            .map_err(|_| format!("Can't convert natural '{}' to integer", n)),
+        Number::Integer(i) => Ok(*i),
+        n => Err(format!("Can't convert {} to integer", n.type_fmt())),
+    }
+}
+
+fn exact_bigint(n: &Number) -> Result<BigInt, String> {
+    match n {
+        Number::Natural(n) => Ok(BigInt::from(*n)),
+        Number::Integer(i) => Ok(BigInt::from(*i)),
+        Number::BigInt(b) => Ok(b.clone()),
+        n => Err(format!("Can't convert {} to bigint", n.type_fmt())),
+    }
+}
+
+fn exact_rational(n: &Number) -> Result<Rational, String> {
+    match n {
+        Number::Rational(r) => Ok(r.clone()),
+        n => exact_bigint(n).map(Rational::from),
+    }
+}
+
+// NOTE: Every whole number up to 2^53 has an exact float representation.
+// A rational is only converted if both of its parts are exact.
+fn exact_float(n: &Number) -> Result<f64, String> {
+    const MAX_EXACT: u64 = 1 << 53;
+    let exact = |b: &BigInt| {
+        b.to_i64()
+            .filter(|i| i.unsigned_abs() <= MAX_EXACT)
+            .map(|i| i as f64)
+    };
+
+    let float = match n {
+        Number::Natural(n) if *n <= MAX_EXACT => Some(*n as f64),
+        Number::Integer(i) if i.unsigned_abs() <= MAX_EXACT => Some(*i as f64),
+        Number::BigInt(b) => exact(b),
+        Number::Rational(r) => {
+            exact(r.numer()).and_then(|numer| Some(numer / exact(r.denom())?))
+        }
+        Number::Float(f) => Some(*f),
+        _ => None,
+    };
+
+    float.ok_or_else(|| {
+        format!(
+            "Can't convert {} to float without losing precision",
+            n.type_fmt()
+        )
+    })
+}
+
+// NOTE: On overflow a natural becomes an integer and an integer a bigint.
+fn widen(n: &Number) -> Option<Number> {
+    match n {
+        Number::Natural(n) => Some(i64::try_from(*n).map_or_else(
+            |_| Number::BigInt(BigInt::from(*n)),
+            Number::Integer,
+        )),
+        Number::Integer(i) => Some(Number::BigInt(BigInt::from(*i))),
+        _ => None,
+    }
+}
+
+// NOTE: Errors are formatted from the operands as they were on the stack,
+// so messages don't show the promoted numbers.
+type Error = fn(&Number, &Number) -> String;
+
+struct Operation {
+    natural: fn(u64, u64) -> Result<u64, Error>,
+    integer: fn(i64, i64) -> Result<i64, Error>,
+    bigint: fn(&BigInt, &BigInt) -> Result<BigInt, Error>,
+    rational: fn(&Rational, &Rational) -> Result<Rational, Error>,
+    float: fn(f64, f64) -> f64,
+}
+
+fn calculate(
+    op: &Operation,
+    left: &Number,
+    right: &Number,
+    strict: bool,
+) -> Result<Number, String> {
+    let (lhs, rhs) = if strict {
+        (left.clone(), right.clone())
+    } else {
+        promote(left, right)?
+    };
 
-    let (num_left, num_right) = match (&left, &right) {
-        (RuntimeValue::Number(lhs), RuntimeValue::Number(rhs)) => (lhs, rhs),
+    let result = match (&lhs, &rhs) {
+        (Number::Natural(a), Number::Natural(b)) => {
+            (op.natural)(*a, *b).map(Number::Natural)
+        }
+        (Number::Integer(a), Number::Integer(b)) => {
+            (op.integer)(*a, *b).map(Number::Integer)
+        }
+        (Number::BigInt(a), Number::BigInt(b)) => {
+            (op.bigint)(a, b).map(Number::BigInt)
+        }
+        (Number::Rational(a), Number::Rational(b)) => {
+            (op.rational)(a, b).map(Number::Rational)
+        }
+        (Number::Float(a), Number::Float(b)) => {
+            Ok(Number::Float((op.float)(*a, *b)))
+        }
         (lhs, rhs) => {
             return Err(format!(
-                "Type error: {}, {}",
+                "Numeric type mismatch: {}, {}",
                 lhs.type_fmt(),
-                rhs.type_fmt()
+                rhs.type_fmt(),
             ))
         }
-    };
+    }
+    .map_err(|error| error(left, right));
 
-    let result = match (num_left, num_right) {
-        (Number::Natural(lhs), Number::Natural(rhs)) => {
-            Number::Natural(op_natural(lhs, rhs)?)
+    // NOTE: Errors that aren't caused by an overflow, like a division by
+    // zero, fail again with the wider type and keep their message.
+    match (result, widen(&lhs), widen(&rhs)) {
+        (Err(msg), Some(wider_lhs), Some(wider_rhs)) if !strict => {
+            calculate(op, &wider_lhs, &wider_rhs, strict).map_err(|_| msg)
         }
-        (Number::Integer(lhs), Number::Integer(rhs)) => {
-            Number::Integer(op_integer(lhs, rhs)?)
-        }
-        (Number::Float(lhs), Number::Float(rhs)) => {
-            Number::Float(op_float(lhs, rhs))
+        (result, _, _) => result,
+    }
+}
+
+fn apply_numeric(
+    op: &Operation,
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    let right = runtime_error::ensure_element(stack)?;
+    let left = runtime_error::ensure_element(stack)?;
+
+    let result = match (&left, &right) {
+        (RuntimeValue::Number(lhs), RuntimeValue::Number(rhs)) => {
+            calculate(op, lhs, rhs, strict)?
         }
-        (_, _) => {
+        (lhs, rhs) => {
             return Err(format!(
-                "Numeric type mismatch: {}, {}",
-                left.type_fmt(),
-                right.type_fmt(),
+                "Type error: {}, {}",
+                lhs.type_fmt(),
+                rhs.type_fmt()
             ))
         }
     };
@@ -53,12 +222,32 @@ where
 
 macro_rules! num_op {
     ($name: ident, $checked_op: ident, $float_op: expr, $err: literal) => {
-        pub fn $name(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+        pub fn $name(
+            stack: &mut Vec<RuntimeValue>,
+            strict: bool,
+        ) -> Result<(), String> {
             apply_numeric(
-                |a, b| a.$checked_op(*b).ok_or_else(|| format!($err, a, b)),
-                |a, b| a.$checked_op(*b).ok_or_else(|| format!($err, a, b)),
-                $float_op,
+                &Operation {
+                    natural: |a, b| {
+                        a.$checked_op(b)
+                            .ok_or::<Error>(|a, b| format!($err, a, b))
+                    },
+                    integer: |a, b| {
+                        a.$checked_op(b)
+                            .ok_or::<Error>(|a, b| format!($err, a, b))
+                    },
+                    bigint: |a, b| {
+                        a.$checked_op(b)
+                            .ok_or::<Error>(|a, b| format!($err, a, b))
+                    },
+                    rational: |a, b| {
+                        a.$checked_op(b)
+                            .ok_or::<Error>(|a, b| format!($err, a, b))
+                    },
+                    float: $float_op,
+                },
                 stack,
+                strict,
             )
         }
     };
@@ -91,3 +280,246 @@ num_op!(
     |a, b| a / b,
     "Division by zero while dividing '{}' and '{}'"
 );
+
+num_op!(
+    apply_rem,
+    checked_rem,
+    |a, b| a % b,
+    "Division by zero while calculating the remainder of '{}' and '{}'"
+);
+
+// NOTE: Unlike '%' the result of 'mod' is never negative.
+num_op!(
+    apply_mod,
+    checked_rem_euclid,
+    |a, b| a.rem_euclid(b),
+    "Division by zero while calculating '{}' modulo '{}'"
+);
+
+fn overflow_pow(a: &Number, b: &Number) -> String {
+    format!("Numeric overflow while raising '{}' to '{}'", a, b)
+}
+
+fn negative_pow(a: &Number, b: &Number) -> String {
+    format!("Can't raise {} to the negative power '{}'", a.type_fmt(), b)
+}
+
+fn fractional_pow(a: &Number, b: &Number) -> String {
+    format!(
+        "Can't raise {} to the fractional power '{}'",
+        a.type_fmt(),
+        b
+    )
+}
+
+fn zero_pow(a: &Number, b: &Number) -> String {
+    format!("Division by zero while raising '{}' to '{}'", a, b)
+}
+
+// NOTE: Only rationals can be raised to negative powers, but every exponent
+// has to be a whole number that fits into 32 bits.
+pub fn apply_pow(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_numeric(
+        &Operation {
+            natural: |a, b| {
+                u32::try_from(b)
+                    .ok()
+                    .and_then(|exp| a.checked_pow(exp))
+                    .ok_or(overflow_pow)
+            },
+            integer: |a, b| {
+                if b < 0 {
+                    return Err(negative_pow);
+                }
+                u32::try_from(b)
+                    .ok()
+                    .and_then(|exp| a.checked_pow(exp))
+                    .ok_or(overflow_pow)
+            },
+            bigint: |a, b| {
+                if b.is_negative() {
+                    return Err(negative_pow);
+                }
+                b.to_u64()
+                    .and_then(|exp| u32::try_from(exp).ok())
+                    .map(|exp| a.pow(exp))
+                    .ok_or(overflow_pow)
+            },
+            rational: |a, b| {
+                if !b.is_whole() {
+                    return Err(fractional_pow);
+                }
+                let exp = b
+                    .numer()
+                    .abs()
+                    .to_u64()
+                    .and_then(|exp| u32::try_from(exp).ok())
+                    .ok_or(overflow_pow as Error)?;
+
+                if b.is_negative() {
+                    a.pow(exp).recip().ok_or(zero_pow)
+                } else {
+                    Ok(a.pow(exp))
+                }
+            },
+            float: f64::powf,
+        },
+        stack,
+        strict,
+    )
+}
+
+pub fn apply_min(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_numeric(
+        &Operation {
+            natural: |a, b| Ok(a.min(b)),
+            integer: |a, b| Ok(a.min(b)),
+            bigint: |a, b| Ok(a.min(b).clone()),
+            rational: |a, b| Ok(a.min(b).clone()),
+            float: f64::min,
+        },
+        stack,
+        strict,
+    )
+}
+
+pub fn apply_max(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    apply_numeric(
+        &Operation {
+            natural: |a, b| Ok(a.max(b)),
+            integer: |a, b| Ok(a.max(b)),
+            bigint: |a, b| Ok(a.max(b).clone()),
+            rational: |a, b| Ok(a.max(b).clone()),
+            float: f64::max,
+        },
+        stack,
+        strict,
+    )
+}
+
+fn absolute(n: &Number, strict: bool) -> Result<Number, String> {
+    let result = match n {
+        Number::Natural(n) => Ok(Number::Natural(*n)),
+        Number::Integer(i) => {
+            i.checked_abs().map(Number::Integer).ok_or_else(|| {
+                format!(
+                    "Numeric overflow while taking the absolute value of '{}'",
+                    i
+                )
+            })
+        }
+        Number::BigInt(b) => Ok(Number::BigInt(b.abs())),
+        Number::Rational(r) => Ok(Number::Rational(r.abs())),
+        Number::Float(f) => Ok(Number::Float(f.abs())),
+    };
+
+    match (result, widen(n)) {
+        (Err(msg), Some(wider)) if !strict => {
+            absolute(&wider, strict).map_err(|_| msg)
+        }
+        (result, _) => result,
+    }
+}
+
+pub fn apply_abs(
+    stack: &mut Vec<RuntimeValue>,
+    strict: bool,
+) -> Result<(), String> {
+    let result = absolute(&runtime_error::ensure_number(stack)?, strict)?;
+
+    stack.push(RuntimeValue::Number(result));
+
+    Ok(())
+}
+
+// NOTE: Naturals, integers and bigints are already whole numbers, so
+// rounding keeps them as they are.
+fn apply_rounding(
+    round: fn(f64) -> f64,
+    round_rational: fn(&Rational) -> Rational,
+    stack: &mut Vec<RuntimeValue>,
+) -> Result<(), String> {
+    let result = match runtime_error::ensure_number(stack)? {
+        Number::Float(f) => Number::Float(round(f)),
+        Number::Rational(r) => Number::Rational(round_rational(&r)),
+        whole => whole,
+    };
+
+    stack.push(RuntimeValue::Number(result));
+
+    Ok(())
+}
+
+pub fn apply_floor(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    apply_rounding(f64::floor, Rational::floor, stack)
+}
+
+pub fn apply_ceil(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    apply_rounding(f64::ceil, Rational::ceil, stack)
+}
+
+pub fn apply_round(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    apply_rounding(f64::round, Rational::round, stack)
+}
+
+pub fn apply_trunc(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    apply_rounding(f64::trunc, Rational::trunc, stack)
+}
+
+// NOTE: These functions are only defined for floats, so every number is
+// converted to a float first.
+macro_rules! float_fn {
+    ($name: ident, $float_fn: expr) => {
+        pub fn $name(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+            let x = to_float(runtime_error::ensure_number(stack)?);
+            stack.push(RuntimeValue::Number(Number::Float($float_fn(x))));
+            Ok(())
+        }
+    };
+}
+
+float_fn!(apply_sqrt, f64::sqrt);
+float_fn!(apply_sin, f64::sin);
+float_fn!(apply_cos, f64::cos);
+float_fn!(apply_tan, f64::tan);
+float_fn!(apply_exp, f64::exp);
+float_fn!(apply_ln, f64::ln);
+float_fn!(apply_log10, f64::log10);
+
+pub fn apply_atan2(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let x = to_float(runtime_error::ensure_number(stack)?);
+    let y = to_float(runtime_error::ensure_number(stack)?);
+
+    stack.push(RuntimeValue::Number(Number::Float(y.atan2(x))));
+
+    Ok(())
+}
+
+pub fn apply_pi(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    stack.push(RuntimeValue::Number(Number::Float(std::f64::consts::PI)));
+    Ok(())
+}
+
+pub fn apply_e(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    stack.push(RuntimeValue::Number(Number::Float(std::f64::consts::E)));
+    Ok(())
+}
+
+fn to_float(n: Number) -> f64 {
+    match n {
+        Number::Natural(n) => n as f64,
+        Number::Integer(i) => i as f64,
+        Number::BigInt(b) => b.to_f64(),
+        Number::Rational(r) => r.to_f64(),
+        Number::Float(f) => f,
+    }
+}
@@ -0,0 +1,42 @@
+use std::io::{self, Write};
+
+// NOTE: Everything a program prints goes through here, so the destination
+// can be swapped out, e.g. for a buffer in tests.
+pub struct Output {
+    stdout: Box<dyn Write>,
+    stderr: Box<dyn Write>,
+}
+
+impl Output {
+    pub fn new(stdout: Box<dyn Write>, stderr: Box<dyn Write>) -> Output {
+        Output { stdout, stderr }
+    }
+
+    pub fn standard() -> Output {
+        Output::new(Box::new(io::stdout()), Box::new(io::stderr()))
+    }
+
+    pub fn stdout(&mut self) -> &mut dyn Write {
+        &mut self.stdout
+    }
+
+    pub fn stderr(&mut self) -> &mut dyn Write {
+        &mut self.stderr
+    }
+
+    pub fn flush(&mut self) -> Result<(), String> {
+        self.stdout
+            .flush()
+            .map_err(|err| write_error("stdout", err))?;
+        self.stderr
+            .flush()
+            .map_err(|err| write_error("stderr", err))
+    }
+}
+
+pub fn write_error(name: &str, err: io::Error) -> String {
+    format!("Couldn't write to {}: {}", name, err)
+}
+
+#[cfg(test)]
+pub mod test;
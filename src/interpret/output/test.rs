@@ -0,0 +1,68 @@
+use super::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// NOTE: A writer whose content can still be inspected after it was moved
+// into an `Output`.
+#[derive(Clone, Default)]
+pub struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+impl Buffer {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "broken pipe"))
+    }
+}
+
+pub fn buffered() -> (Output, Buffer, Buffer) {
+    let stdout = Buffer::default();
+    let stderr = Buffer::default();
+    let output =
+        Output::new(Box::new(stdout.clone()), Box::new(stderr.clone()));
+
+    (output, stdout, stderr)
+}
+
+#[test]
+fn test_separate_streams() {
+    let (mut output, stdout, stderr) = buffered();
+
+    write!(output.stdout(), "out").unwrap();
+    write!(output.stderr(), "err").unwrap();
+    assert_eq!(output.flush(), Ok(()));
+
+    assert_eq!(stdout.text(), "out");
+    assert_eq!(stderr.text(), "err");
+}
+
+#[test]
+fn test_flush_error() {
+    let mut output = Output::new(Box::new(Buffer::default()), Box::new(Broken));
+
+    assert_eq!(
+        output.flush(),
+        Err("Couldn't write to stderr: broken pipe".to_owned())
+    );
+}
@@ -0,0 +1,102 @@
+use super::runtime_error;
+use super::runtime_value::RuntimeValue;
+use crate::lex::Number;
+
+use regex::Regex;
+use std::collections::HashMap;
+
+// NOTE: Compiled regexes by pattern, so matching in a loop doesn't recompile
+// the same pattern on every iteration. Cloning a Regex is cheap.
+pub type RegexCache = HashMap<String, Regex>;
+
+fn ensure_regex(
+    stack: &mut Vec<RuntimeValue>,
+    regexes: &mut RegexCache,
+) -> Result<Regex, String> {
+    let pattern = runtime_error::ensure_string(stack)?;
+
+    if let Some(regex) = regexes.get(&pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Regex::new(&pattern)
+        .map_err(|err| format!("Invalid regex '{}': {}", pattern, err))?;
+    regexes.insert(pattern, regex.clone());
+
+    Ok(regex)
+}
+
+pub fn apply_rematch(
+    stack: &mut Vec<RuntimeValue>,
+    regexes: &mut RegexCache,
+) -> Result<(), String> {
+    let regex = ensure_regex(stack, regexes)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    stack.push(RuntimeValue::Boolean(regex.is_match(&string)));
+
+    Ok(())
+}
+
+pub fn apply_refind(
+    stack: &mut Vec<RuntimeValue>,
+    regexes: &mut RegexCache,
+) -> Result<(), String> {
+    let regex = ensure_regex(stack, regexes)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    match regex.find(&string) {
+        Some(found) => {
+            stack.push(RuntimeValue::String(found.as_str().to_owned()));
+            stack.push(RuntimeValue::Boolean(true));
+        }
+        None => {
+            stack.push(RuntimeValue::String(String::new()));
+            stack.push(RuntimeValue::Boolean(false));
+        }
+    }
+
+    Ok(())
+}
+
+// NOTE: Pushes the whole match and every group of the first match. Groups
+// that didn't participate in the match are empty strings. Without a match
+// only the count 0 is pushed.
+pub fn apply_recaptures(
+    stack: &mut Vec<RuntimeValue>,
+    regexes: &mut RegexCache,
+) -> Result<(), String> {
+    let regex = ensure_regex(stack, regexes)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    let count = match regex.captures(&string) {
+        Some(captures) => {
+            for group in captures.iter() {
+                let group = group.map_or("", |group| group.as_str());
+                stack.push(RuntimeValue::String(group.to_owned()));
+            }
+            captures.len() as u64
+        }
+        None => 0,
+    };
+
+    stack.push(RuntimeValue::Number(Number::Natural(count)));
+
+    Ok(())
+}
+
+pub fn apply_rereplace(
+    stack: &mut Vec<RuntimeValue>,
+    regexes: &mut RegexCache,
+) -> Result<(), String> {
+    let replacement = runtime_error::ensure_string(stack)?;
+    let regex = ensure_regex(stack, regexes)?;
+    let string = runtime_error::ensure_string_ref(stack)?;
+
+    *string = regex.replace_all(string, replacement.as_str()).into_owned();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test;
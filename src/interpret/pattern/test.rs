@@ -0,0 +1,55 @@
+use super::*;
+
+fn string(s: &str) -> RuntimeValue {
+    RuntimeValue::String(s.to_owned())
+}
+
+#[test]
+fn test_recaptures() {
+    let stack =
+        &mut vec![string("user=alice id=42"), string(r"(\w+)=(\w+)(x)?")];
+    assert_eq!(apply_recaptures(stack, &mut RegexCache::new()), Ok(()));
+    assert_eq!(
+        stack,
+        &[
+            string("user=alice"),
+            string("user"),
+            string("alice"),
+            string(""),
+            RuntimeValue::Number(Number::Natural(4)),
+        ]
+    );
+
+    let stack = &mut vec![string("nothing"), string(r"\d+")];
+    assert_eq!(apply_recaptures(stack, &mut RegexCache::new()), Ok(()));
+    assert_eq!(stack, &[RuntimeValue::Number(Number::Natural(0))]);
+}
+
+#[test]
+fn test_invalid_regex() {
+    let stack = &mut vec![string("text"), string("(unclosed")];
+    let error = apply_rematch(stack, &mut RegexCache::new()).unwrap_err();
+
+    assert!(
+        error.starts_with("Invalid regex '(unclosed': "),
+        "{}",
+        error
+    );
+    assert!(error.contains("unclosed group"), "{}", error);
+}
+
+#[test]
+fn test_regex_cache() {
+    let regexes = &mut RegexCache::new();
+
+    let stack = &mut vec![string("abc123"), string(r"\d+")];
+    assert_eq!(apply_rematch(stack, regexes), Ok(()));
+    let stack = &mut vec![string("no digits"), string(r"\d+")];
+    assert_eq!(apply_rematch(stack, regexes), Ok(()));
+    assert_eq!(stack, &[RuntimeValue::Boolean(false)]);
+    assert_eq!(regexes.len(), 1);
+
+    let stack = &mut vec![string("text"), string("(unclosed")];
+    assert!(apply_rematch(stack, regexes).is_err());
+    assert_eq!(regexes.len(), 1);
+}
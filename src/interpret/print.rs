@@ -1,21 +1,79 @@
+use super::output::{write_error, Output};
 use super::runtime_error;
 use super::runtime_value::RuntimeValue;
 
-pub fn apply_print(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+use std::io::Write;
+
+pub fn apply_print(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+) -> Result<(), String> {
     let value = runtime_error::ensure_element(stack)?;
-    print!("{}", value);
-    Ok(())
+    write!(output.stdout(), "{}", value)
+        .map_err(|err| write_error("stdout", err))
+}
+
+pub fn apply_println(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+) -> Result<(), String> {
+    let value = runtime_error::ensure_element(stack)?;
+    writeln!(output.stdout(), "{}", value)
+        .map_err(|err| write_error("stdout", err))
+}
+
+pub fn apply_eprint(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+) -> Result<(), String> {
+    let value = runtime_error::ensure_element(stack)?;
+    write!(output.stderr(), "{}", value)
+        .map_err(|err| write_error("stderr", err))
+}
+
+pub fn apply_flush(output: &mut Output) -> Result<(), String> {
+    output.flush()
 }
 
-pub fn apply_showstack(stack: &mut [RuntimeValue]) -> Result<(), String> {
+pub fn apply_showstack(
+    stack: &mut [RuntimeValue],
+    output: &mut Output,
+) -> Result<(), String> {
+    let out = output.stdout();
+
     for (index, element) in stack.iter().rev().enumerate().rev() {
-        print!(" [{}]:\t", index);
+        write!(out, " [{}]:\t", index)
+            .and_then(|_| print_element(out, element))
+            .map_err(|err| write_error("stdout", err))?;
+    }
+
+    Ok(())
+}
 
-        match element {
-            RuntimeValue::String(string) => println!("\"{}\"", string),
-            value => println!("{}", value),
-        }
+pub fn show_variables(
+    variables: &[(&str, &RuntimeValue)],
+    output: &mut Output,
+) -> Result<(), String> {
+    let out = output.stdout();
+
+    for (name, value) in variables {
+        write!(out, " {}:\t", name)
+            .and_then(|_| print_element(out, value))
+            .map_err(|err| write_error("stdout", err))?;
     }
 
     Ok(())
 }
+
+fn print_element(
+    out: &mut dyn Write,
+    element: &RuntimeValue,
+) -> std::io::Result<()> {
+    match element {
+        RuntimeValue::String(string) => writeln!(out, "\"{}\"", string),
+        value => writeln!(out, "{}", value),
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,49 @@
+use super::*;
+use crate::interpret::output::test::buffered;
+use crate::lex::Number;
+
+fn string(s: &str) -> RuntimeValue {
+    RuntimeValue::String(s.to_owned())
+}
+
+#[test]
+fn test_print_streams() {
+    let (mut output, stdout, stderr) = buffered();
+    let stack = &mut vec![
+        RuntimeValue::Number(Number::Natural(3)),
+        string("error"),
+        string("line"),
+        string("text "),
+    ];
+
+    assert_eq!(apply_print(stack, &mut output), Ok(()));
+    assert_eq!(apply_println(stack, &mut output), Ok(()));
+    assert_eq!(apply_eprint(stack, &mut output), Ok(()));
+    assert_eq!(apply_println(stack, &mut output), Ok(()));
+    assert_eq!(apply_flush(&mut output), Ok(()));
+
+    assert!(stack.is_empty());
+    assert_eq!(stdout.text(), "text line\n3\n");
+    assert_eq!(stderr.text(), "error");
+}
+
+#[test]
+fn test_print_empty_stack() {
+    let (mut output, stdout, _) = buffered();
+
+    assert_eq!(
+        apply_println(&mut vec![], &mut output),
+        Err("Stack underflow".to_owned())
+    );
+    assert_eq!(stdout.text(), "");
+}
+
+#[test]
+fn test_showstack() {
+    let (mut output, stdout, _) = buffered();
+    let stack = &mut [string("a"), RuntimeValue::Boolean(true)];
+
+    assert_eq!(apply_showstack(stack, &mut output), Ok(()));
+    assert_eq!(stdout.text(), " [1]:\t\"a\"\n [0]:\ttrue\n");
+    assert_eq!(stack.len(), 2);
+}
@@ -1,12 +1,75 @@
+use super::output::Output;
 use super::runtime_error;
-use super::runtime_value::RuntimeValue;
+use super::runtime_value::{Function, RuntimeValue};
 use super::Interpreter;
 use super::State;
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
-use std::io::{self, BufRead};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
 use std::rc::Rc;
 
+// NOTE: How bytes read as input are turned into strings. Without 'lossy'
+// invalid UTF-8 is an error, otherwise it's replaced with U+FFFD.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InputMode {
+    pub lossy: bool,
+    pub keep_cr: bool,
+}
+
+impl InputMode {
+    fn decode(self, name: &str, bytes: Vec<u8>) -> Result<String, String> {
+        if self.lossy {
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        } else {
+            String::from_utf8(bytes)
+                .map_err(|_| format!("{}: Invalid UTF-8 in input", name))
+        }
+    }
+
+    fn read_line<R: BufRead>(
+        self,
+        reader: &mut R,
+        name: &str,
+    ) -> Result<Option<String>, String> {
+        let mut bytes = vec![];
+        let read = reader
+            .read_until(b'\n', &mut bytes)
+            .map_err(|err| format!("{}: {}", name, err))?;
+
+        if read == 0 {
+            return Ok(None);
+        }
+
+        if bytes.last() == Some(&b'\n') {
+            bytes.pop();
+            if !self.keep_cr && bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+        }
+
+        self.decode(name, bytes).map(Some)
+    }
+
+    pub fn read_all<R: Read>(
+        self,
+        reader: &mut R,
+        name: &str,
+    ) -> Result<String, String> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|err| format!("{}: {}", name, err))?;
+        let text = self.decode(name, bytes)?;
+
+        if self.keep_cr {
+            Ok(text)
+        } else {
+            Ok(text.replace("\r\n", "\n"))
+        }
+    }
+}
+
 pub fn apply_readlines(
     state: &mut State,
     source: &Rc<ProgramSource>,
@@ -15,9 +78,80 @@ pub fn apply_readlines(
     let to_pile_error =
         |msg| PileError::in_range(Rc::clone(source), lines, msg);
     let func = runtime_error::ensure_function(state).map_err(to_pile_error)?;
+    // NOTE: Prompts printed before reading have to show up first.
+    state.output.flush().map_err(to_pile_error)?;
 
-    for line in io::stdin().lock().lines() {
-        state.stack.push(RuntimeValue::String(line.unwrap()));
+    read_lines(io::stdin().lock(), "stdin", func, state, source)
+}
+
+pub fn apply_readfilelines(
+    state: &mut State,
+    source: &Rc<ProgramSource>,
+) -> Result<(), PileError> {
+    let lines = state.current_lines;
+    let to_pile_error =
+        |msg| PileError::in_range(Rc::clone(source), lines, msg);
+    let path = runtime_error::ensure_string(&mut state.stack)
+        .map_err(to_pile_error)?;
+    let func = runtime_error::ensure_function(state).map_err(to_pile_error)?;
+
+    let file = File::open(&path)
+        .map_err(|err| to_pile_error(format!("{}: {}", path, err)))?;
+
+    read_lines(BufReader::new(file), &path, func, state, source)
+}
+
+pub fn apply_readline(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+    mode: InputMode,
+) -> Result<(), String> {
+    output.flush()?;
+
+    match mode.read_line(&mut io::stdin().lock(), "stdin")? {
+        Some(line) => {
+            stack.push(RuntimeValue::String(line));
+            stack.push(RuntimeValue::Boolean(true));
+        }
+        None => {
+            stack.push(RuntimeValue::String(String::new()));
+            stack.push(RuntimeValue::Boolean(false));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn apply_readall(
+    stack: &mut Vec<RuntimeValue>,
+    output: &mut Output,
+    mode: InputMode,
+) -> Result<(), String> {
+    output.flush()?;
+
+    let text = mode.read_all(&mut io::stdin().lock(), "stdin")?;
+    stack.push(RuntimeValue::String(text));
+
+    Ok(())
+}
+
+fn read_lines<R: BufRead>(
+    mut reader: R,
+    name: &str,
+    func: Function,
+    state: &mut State,
+    source: &Rc<ProgramSource>,
+) -> Result<(), PileError> {
+    let lines = state.current_lines;
+    let to_pile_error =
+        |msg| PileError::in_range(Rc::clone(source), lines, msg);
+
+    while let Some(line) = state
+        .input_mode
+        .read_line(&mut reader, name)
+        .map_err(to_pile_error)?
+    {
+        state.stack.push(RuntimeValue::String(line));
         Interpreter::call(&func.exprs, state, &func.source)?;
         let repeat = runtime_error::ensure_bool(&mut state.stack)
             .map_err(to_pile_error)?;
@@ -29,3 +163,6 @@ pub fn apply_readlines(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,93 @@
+use super::*;
+
+const STRICT: InputMode = InputMode {
+    lossy: false,
+    keep_cr: false,
+};
+
+fn read_lines(mode: InputMode, input: &[u8]) -> Result<Vec<String>, String> {
+    let mut reader = input;
+    let mut lines = vec![];
+
+    while let Some(line) = mode.read_line(&mut reader, "input")? {
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+#[test]
+fn test_read_line_endings() {
+    let input = b"first\r\nsecond\nthird\r";
+
+    assert_eq!(
+        read_lines(STRICT, input),
+        Ok(vec![
+            "first".to_owned(),
+            "second".to_owned(),
+            "third\r".to_owned()
+        ])
+    );
+    assert_eq!(
+        read_lines(
+            InputMode {
+                keep_cr: true,
+                ..STRICT
+            },
+            input
+        ),
+        Ok(vec![
+            "first\r".to_owned(),
+            "second".to_owned(),
+            "third\r".to_owned()
+        ])
+    );
+    assert_eq!(read_lines(STRICT, b"\n\n"), Ok(vec![String::new(); 2]));
+    assert_eq!(read_lines(STRICT, b""), Ok(vec![]));
+}
+
+#[test]
+fn test_read_invalid_utf8() {
+    let input = b"caf\xe9\nok\n";
+
+    assert_eq!(
+        read_lines(STRICT, input),
+        Err("input: Invalid UTF-8 in input".to_owned())
+    );
+    assert_eq!(
+        read_lines(
+            InputMode {
+                lossy: true,
+                ..STRICT
+            },
+            input
+        ),
+        Ok(vec!["caf\u{fffd}".to_owned(), "ok".to_owned()])
+    );
+}
+
+#[test]
+fn test_read_all() {
+    let input = b"a\r\nb\xff\r\n";
+    let lossy = InputMode {
+        lossy: true,
+        ..STRICT
+    };
+
+    assert_eq!(
+        STRICT.read_all(&mut &input[..], "input"),
+        Err("input: Invalid UTF-8 in input".to_owned())
+    );
+    assert_eq!(
+        lossy.read_all(&mut &input[..], "input"),
+        Ok("a\nb\u{fffd}\n".to_owned())
+    );
+    assert_eq!(
+        InputMode {
+            keep_cr: true,
+            ..lossy
+        }
+        .read_all(&mut &input[..], "input"),
+        Ok("a\r\nb\u{fffd}\r\n".to_owned())
+    );
+}
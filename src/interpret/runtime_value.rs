@@ -6,6 +6,8 @@ pub use crate::lex::Number;
 pub use crate::lex::Operator;
 pub use crate::parse::Expr;
 
+use crate::formatting;
+use crate::lex;
 use crate::program_source::ProgramSource;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -26,15 +28,7 @@ impl RuntimeValue {
     pub fn type_fmt(&self) -> String {
         match self {
             RuntimeValue::Function { .. } => "function".to_string(),
-            RuntimeValue::Number(Number::Natural(n)) => {
-                format!("natural '{}'", n)
-            }
-            RuntimeValue::Number(Number::Integer(i)) => {
-                format!("integer '{}'", i)
-            }
-            RuntimeValue::Number(Number::Float(fl)) => {
-                format!("float '{}'", fl)
-            }
+            RuntimeValue::Number(n) => n.type_fmt(),
             RuntimeValue::String(s) => format!("string '{}'", s),
             RuntimeValue::Boolean(true) => format!("boolean '{}'", true),
             RuntimeValue::Boolean(false) => format!("boolean '{}'", false),
@@ -42,15 +36,26 @@ impl RuntimeValue {
     }
 }
 
+impl RuntimeValue {
+    pub fn source_fmt(&self) -> String {
+        match self {
+            RuntimeValue::Function(Function { exprs, .. }) => {
+                formatting::function_source(exprs)
+            }
+            RuntimeValue::Number(n) => n.source_fmt(),
+            RuntimeValue::String(s) => lex::string_source_fmt(s),
+            RuntimeValue::Boolean(b) => b.to_string(),
+        }
+    }
+}
+
 impl fmt::Display for RuntimeValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             RuntimeValue::Function(Function { exprs, .. }) => {
                 write!(f, "function @ {:p}", exprs)
             }
-            RuntimeValue::Number(Number::Natural(n)) => write!(f, "{}", n),
-            RuntimeValue::Number(Number::Integer(i)) => write!(f, "{}", i),
-            RuntimeValue::Number(Number::Float(fl)) => write!(f, "{}", fl),
+            RuntimeValue::Number(n) => write!(f, "{}", n),
             RuntimeValue::String(s) => write!(f, "{}", s),
             RuntimeValue::Boolean(true) => write!(f, "true"),
             RuntimeValue::Boolean(false) => write!(f, "false"),
@@ -69,6 +74,14 @@ impl PartialOrd for RuntimeValue {
                 RuntimeValue::Number(Number::Integer(left)),
                 RuntimeValue::Number(Number::Integer(right)),
             ) => Some(left.cmp(right)),
+            (
+                RuntimeValue::Number(Number::BigInt(left)),
+                RuntimeValue::Number(Number::BigInt(right)),
+            ) => Some(left.cmp(right)),
+            (
+                RuntimeValue::Number(Number::Rational(left)),
+                RuntimeValue::Number(Number::Rational(right)),
+            ) => Some(left.cmp(right)),
             (
                 RuntimeValue::Number(Number::Float(left)),
                 RuntimeValue::Number(Number::Float(right)),
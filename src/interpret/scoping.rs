@@ -52,6 +52,15 @@ impl ScopeStack {
         }
     }
 
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, &RuntimeValue)> {
+        self.lookup.iter().map(|(varname, entry)| {
+            (
+                varname.as_ref(),
+                entry.shadows.last().unwrap_or(&entry.original),
+            )
+        })
+    }
+
     pub fn save(&mut self, varname: &str) {
         let default_value = RuntimeValue::Boolean(false);
 
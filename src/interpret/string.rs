@@ -2,7 +2,7 @@ use super::runtime_error;
 use super::runtime_value::RuntimeValue;
 use runtime_error::Number;
 
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
 pub fn apply_concat(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
     let right = runtime_error::ensure_string(stack)?;
@@ -58,34 +58,8 @@ pub fn apply_trim(stack: &mut [RuntimeValue]) -> Result<(), String> {
     Ok(())
 }
 
-pub fn apply_format(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    let format = runtime_error::ensure_string(stack)?;
-    let mut split = format.rsplit("{}");
-    let mut result = vec![String::from(split.next().unwrap())];
-
-    for part in split {
-        let value = runtime_error::ensure_element(stack)?;
-        result.push(value.to_string());
-        result.push(part.to_string());
-    }
-
-    result.reverse();
-    stack.push(RuntimeValue::String(result.join("")));
-
-    Ok(())
-}
-
 pub fn apply_index(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
-    let index = runtime_error::ensure_element(stack)?;
-    let index = match index {
-        RuntimeValue::Number(Number::Natural(n)) => n,
-        _ => {
-            return Err(format!(
-                "Can't use {} as string index",
-                index.type_fmt()
-            ))
-        }
-    };
+    let index = ensure_natural(stack, "string index")?;
     let string = runtime_error::ensure_string_ref(stack)?;
     let c = match string.chars().nth(index as usize) {
         Some(c) => c,
@@ -102,5 +76,159 @@ pub fn apply_index(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
     Ok(())
 }
 
-#[cfg(test)]
-mod test;
+// NOTE: An empty separator splits the string into its chars.
+pub fn apply_split(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let separator = runtime_error::ensure_string(stack)?;
+    let string = runtime_error::ensure_string(stack)?;
+    let pieces: Vec<String> = if separator.is_empty() {
+        string.chars().map(String::from).collect()
+    } else {
+        string.split(&separator).map(str::to_owned).collect()
+    };
+    let count = pieces.len() as u64;
+
+    stack.extend(pieces.into_iter().map(RuntimeValue::String));
+    stack.push(RuntimeValue::Number(Number::Natural(count)));
+
+    Ok(())
+}
+
+pub fn apply_join(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let separator = runtime_error::ensure_string(stack)?;
+    let count = ensure_natural(stack, "string count")?;
+    let mut pieces = vec![];
+
+    for _ in 0..count {
+        pieces.push(runtime_error::ensure_string(stack)?);
+    }
+
+    pieces.reverse();
+    stack.push(RuntimeValue::String(pieces.join(&separator)));
+
+    Ok(())
+}
+
+pub fn apply_replace(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let to = runtime_error::ensure_string(stack)?;
+    let from = runtime_error::ensure_string(stack)?;
+    let string = runtime_error::ensure_string_ref(stack)?;
+
+    *string = string.replace(&from, &to);
+
+    Ok(())
+}
+
+pub fn apply_substring(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let end = ensure_natural(stack, "string index")?;
+    let start = ensure_natural(stack, "string index")?;
+    let string = runtime_error::ensure_string_ref(stack)?;
+    let length = string.chars().count() as u64;
+
+    if start > end || end > length {
+        return Err(format!(
+            "Invalid range {}..{} for string \"{}\"",
+            start, end, string
+        ));
+    }
+
+    *string = string
+        .chars()
+        .skip(start as usize)
+        .take((end - start) as usize)
+        .collect();
+
+    Ok(())
+}
+
+pub fn apply_startswith(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let prefix = runtime_error::ensure_string(stack)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    stack.push(RuntimeValue::Boolean(string.starts_with(&prefix)));
+
+    Ok(())
+}
+
+pub fn apply_endswith(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let suffix = runtime_error::ensure_string(stack)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    stack.push(RuntimeValue::Boolean(string.ends_with(&suffix)));
+
+    Ok(())
+}
+
+pub fn apply_find(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let pattern = runtime_error::ensure_string(stack)?;
+    let string = runtime_error::ensure_string(stack)?;
+
+    match string.find(&pattern) {
+        Some(byte_index) => {
+            let index = string[..byte_index].chars().count() as u64;
+            stack.push(RuntimeValue::Number(Number::Natural(index)));
+            stack.push(RuntimeValue::Boolean(true));
+        }
+        None => {
+            stack.push(RuntimeValue::Number(Number::Natural(0)));
+            stack.push(RuntimeValue::Boolean(false));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn apply_repeat(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let count = ensure_natural(stack, "repeat count")?;
+    let string = runtime_error::ensure_string_ref(stack)?;
+
+    let too_long = || format!("Can't repeat \"{}\" {} times", string, count);
+    let count: usize = count.try_into().map_err(|_| too_long())?;
+    string.len().checked_mul(count).ok_or_else(too_long)?;
+
+    *string = string.repeat(count);
+
+    Ok(())
+}
+
+pub fn apply_reverse(stack: &mut [RuntimeValue]) -> Result<(), String> {
+    let string = runtime_error::ensure_string_ref(stack)?;
+
+    *string = string.chars().rev().collect();
+
+    Ok(())
+}
+
+pub fn apply_ord(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let string = runtime_error::ensure_string(stack)?;
+    let mut chars = string.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+            stack.push(RuntimeValue::Number(Number::Natural(c as u64)));
+            Ok(())
+        }
+        _ => Err(format!("Expected a single char found \"{}\"", string)),
+    }
+}
+
+pub fn apply_chr(stack: &mut Vec<RuntimeValue>) -> Result<(), String> {
+    let code = ensure_natural(stack, "code point")?;
+    let c = u32::try_from(code)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| format!("Invalid code point {}", code))?;
+
+    stack.push(RuntimeValue::String(c.to_string()));
+
+    Ok(())
+}
+
+fn ensure_natural(
+    stack: &mut Vec<RuntimeValue>,
+    what: &str,
+) -> Result<u64, String> {
+    match runtime_error::ensure_element(stack)? {
+        RuntimeValue::Number(Number::Natural(n)) => Ok(n),
+        value => Err(format!("Can't use {} as {}", value.type_fmt(), what)),
+    }
+}
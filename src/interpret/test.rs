@@ -1,18 +1,32 @@
 use super::*;
-use crate::lex::Lexer;
+use crate::lex::BigInt;
 use crate::lex::Number;
+use crate::lex::{self, Lexer};
 use crate::locals;
 use crate::parse::Parser;
 use crate::program_source::ProgramSource;
 use crate::using;
 
 fn expect_value(prog: &str, value: Result<&RuntimeValue, PileError>) {
+    expect_value_in_mode(prog, false, value)
+}
+
+fn expect_strict_value(prog: &str, value: Result<&RuntimeValue, PileError>) {
+    expect_value_in_mode(prog, true, value)
+}
+
+fn expect_value_in_mode(
+    prog: &str,
+    strict: bool,
+    value: Result<&RuntimeValue, PileError>,
+) {
     let lexer = Lexer::new(prog, Rc::new(ProgramSource::Stdin));
     let parser = Parser::new(lexer);
     let ast = parser.parse().expect("invalid program");
     let ast = locals::translate(ast);
-    let ast = using::resolve(ast).expect("resolve failed");
+    let ast = using::resolve(ast, &[]).expect("resolve failed");
     let mut interpreter = Interpreter::new(ast, 10, false);
+    interpreter.set_strict(strict);
 
     let result = match interpreter.run() {
         Ok(Some(value)) => Ok(value),
@@ -67,6 +81,172 @@ fn test_float_arith() {
     expect_value("1.0 2.0 -", Ok(&RuntimeValue::Number(Number::Float(-1.0))));
 }
 
+#[test]
+fn test_remainder_modulo() {
+    expect_value("7 3 %", Ok(&RuntimeValue::Number(Number::Natural(1))));
+    expect_value(
+        "-7 3 integer %",
+        Ok(&RuntimeValue::Number(Number::Integer(-1))),
+    );
+    expect_value(
+        "-7 3 integer mod",
+        Ok(&RuntimeValue::Number(Number::Integer(2))),
+    );
+    expect_value(
+        "7 integer -3 mod",
+        Ok(&RuntimeValue::Number(Number::Integer(1))),
+    );
+    expect_value("-7.5 2.0 %", Ok(&RuntimeValue::Number(Number::Float(-1.5))));
+    expect_value(
+        "-7.5 2.0 mod",
+        Ok(&RuntimeValue::Number(Number::Float(0.5))),
+    );
+    expect_value(
+        "7 0 %",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Division by zero while calculating the remainder of '7' and '0'"
+                .to_owned(),
+        )),
+    );
+    expect_value(
+        "-7 0 integer mod",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Division by zero while calculating '-7' modulo '0'".to_owned(),
+        )),
+    );
+    expect_value(
+        "7 3 integer %",
+        Ok(&RuntimeValue::Number(Number::Integer(1))),
+    );
+    expect_strict_value(
+        "7 3 integer %",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric type mismatch: natural '7', integer '3'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_pow() {
+    expect_value("2 10 pow", Ok(&RuntimeValue::Number(Number::Natural(1024))));
+    expect_value(
+        "-2 3 integer pow",
+        Ok(&RuntimeValue::Number(Number::Integer(-8))),
+    );
+    expect_value("4.0 0.5 pow", Ok(&RuntimeValue::Number(Number::Float(2.0))));
+    expect_value(
+        "2 64 pow",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "18446744073709551616".parse().unwrap(),
+        ))),
+    );
+    expect_strict_value(
+        "2 64 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric overflow while raising '2' to '64'".to_owned(),
+        )),
+    );
+    expect_value(
+        "2 integer -1 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't raise integer '2' to the negative power '-1'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_min_max_abs() {
+    expect_value("3 5 min", Ok(&RuntimeValue::Number(Number::Natural(3))));
+    expect_value("3 5 max", Ok(&RuntimeValue::Number(Number::Natural(5))));
+    expect_value("-3 -5 max", Ok(&RuntimeValue::Number(Number::Integer(-3))));
+    expect_value(
+        "1.5 -2.5 min",
+        Ok(&RuntimeValue::Number(Number::Float(-2.5))),
+    );
+    expect_value("-4 abs", Ok(&RuntimeValue::Number(Number::Integer(4))));
+    expect_value("-2.5 abs", Ok(&RuntimeValue::Number(Number::Float(2.5))));
+    expect_value("4 abs", Ok(&RuntimeValue::Number(Number::Natural(4))));
+    expect_value(
+        "-9223372036854775808 abs",
+        Ok(&RuntimeValue::Number(Number::BigInt(BigInt::from(
+            9223372036854775808u64,
+        )))),
+    );
+    expect_strict_value(
+        "-9223372036854775808 abs",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric overflow while taking the absolute value of \
+             '-9223372036854775808'"
+                .to_owned(),
+        )),
+    );
+    expect_value(
+        "\"a\" abs",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected number found string 'a'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_rounding() {
+    expect_value("2.7 floor", Ok(&RuntimeValue::Number(Number::Float(2.0))));
+    expect_value("-2.7 floor", Ok(&RuntimeValue::Number(Number::Float(-3.0))));
+    expect_value("2.2 ceil", Ok(&RuntimeValue::Number(Number::Float(3.0))));
+    expect_value("2.5 round", Ok(&RuntimeValue::Number(Number::Float(3.0))));
+    expect_value("-2.5 round", Ok(&RuntimeValue::Number(Number::Float(-3.0))));
+    expect_value("-2.7 trunc", Ok(&RuntimeValue::Number(Number::Float(-2.0))));
+    expect_value("7 floor", Ok(&RuntimeValue::Number(Number::Natural(7))));
+    expect_value("-7 round", Ok(&RuntimeValue::Number(Number::Integer(-7))));
+}
+
+#[test]
+fn test_float_functions() {
+    expect_value("16 sqrt", Ok(&RuntimeValue::Number(Number::Float(4.0))));
+    expect_value("0 sin", Ok(&RuntimeValue::Number(Number::Float(0.0))));
+    expect_value("0.0 cos", Ok(&RuntimeValue::Number(Number::Float(1.0))));
+    expect_value(
+        "0 integer tan",
+        Ok(&RuntimeValue::Number(Number::Float(0.0))),
+    );
+    expect_value("0 1 atan2", Ok(&RuntimeValue::Number(Number::Float(0.0))));
+    expect_value("0 exp", Ok(&RuntimeValue::Number(Number::Float(1.0))));
+    expect_value("1 ln", Ok(&RuntimeValue::Number(Number::Float(0.0))));
+    expect_value("1000 log10", Ok(&RuntimeValue::Number(Number::Float(3.0))));
+    expect_value(
+        "pi",
+        Ok(&RuntimeValue::Number(Number::Float(std::f64::consts::PI))),
+    );
+    expect_value(
+        "e",
+        Ok(&RuntimeValue::Number(Number::Float(std::f64::consts::E))),
+    );
+    expect_value("e ln", Ok(&RuntimeValue::Number(Number::Float(1.0))));
+    expect_value("1 1 atan2 4.0 * pi =", Ok(&RuntimeValue::Boolean(true)));
+    expect_value(
+        "true sqrt",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected number found boolean 'true'".to_owned(),
+        )),
+    );
+}
+
 #[test]
 fn test_if1() {
     expect_value(
@@ -203,7 +383,7 @@ fn test_invalid_compare() {
             "Can't compare natural '1' and string 'str'".to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "1.1 1 =",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -211,7 +391,7 @@ fn test_invalid_compare() {
             "Can't compare float '1.1' and natural '1'".to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "-1 1 >=",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -221,6 +401,132 @@ fn test_invalid_compare() {
     );
 }
 
+#[test]
+fn test_promotion() {
+    expect_value("1 -2 +", Ok(&RuntimeValue::Number(Number::Integer(-1))));
+    expect_value("7 -2 /", Ok(&RuntimeValue::Number(Number::Integer(-3))));
+    expect_value("1 0.5 +", Ok(&RuntimeValue::Number(Number::Float(1.5))));
+    expect_value("-3 0.5 *", Ok(&RuntimeValue::Number(Number::Float(-1.5))));
+    expect_value("3 -5 max", Ok(&RuntimeValue::Number(Number::Integer(3))));
+    expect_value(
+        "2 0.5 pow",
+        Ok(&RuntimeValue::Number(Number::Float(
+            std::f64::consts::SQRT_2,
+        ))),
+    );
+    expect_value("1 2 -", Ok(&RuntimeValue::Number(Number::Integer(-1))));
+    expect_value(
+        "18446744073709551615 -1 +",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "18446744073709551614".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "9007199254740993 0.5 +",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't convert natural '9007199254740993' to float without \
+             losing precision"
+                .to_owned(),
+        )),
+    );
+    expect_value(
+        "-9007199254740993 0.5 +",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't convert integer '-9007199254740993' to float without \
+             losing precision"
+                .to_owned(),
+        )),
+    );
+    expect_value("1 1.0 =", Ok(&RuntimeValue::Boolean(true)));
+    expect_value("1 1.5 <", Ok(&RuntimeValue::Boolean(true)));
+    expect_value("-1 1 <", Ok(&RuntimeValue::Boolean(true)));
+    expect_value("2 -1.5 >=", Ok(&RuntimeValue::Boolean(true)));
+    expect_value(
+        "18446744073709551615 -1 >",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_strict_value(
+        "1 1.0 =",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't compare natural '1' and float '1'".to_owned(),
+        )),
+    );
+    expect_strict_value("1 1 =", Ok(&RuntimeValue::Boolean(true)));
+    expect_strict_value(
+        "1.5 2.5 +",
+        Ok(&RuntimeValue::Number(Number::Float(4.0))),
+    );
+
+    let one = RuntimeValue::Number(Number::Natural(1));
+    let one_float = RuntimeValue::Number(Number::Float(1.0));
+    assert_ne!(one, one_float);
+    assert_eq!(one.partial_cmp(&one_float), None);
+}
+
+#[test]
+fn test_exact_float_compare() {
+    expect_value("9007199254740993 0.5 >", Ok(&RuntimeValue::Boolean(true)));
+    expect_value(
+        "9007199254740993 9007199254740992.0 >",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "9007199254740993 9007199254740992.0 =",
+        Ok(&RuntimeValue::Boolean(false)),
+    );
+    expect_value(
+        "9007199254740992 9007199254740992.0 =",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "-9007199254740993 integer -9007199254740992.0 <",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "99999999999999999999 1.0 <",
+        Ok(&RuntimeValue::Boolean(false)),
+    );
+    expect_value(
+        "100000000000000000000.0 100000000000000000000 =",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "1.0 0.0 / 99999999999999999999 >",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "0.0 0.0 / 1 <",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't compare float 'NaN' and natural '1'".to_string(),
+        )),
+    );
+    expect_strict_value(
+        "9007199254740993 0.5 >",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't compare natural '9007199254740993' and float '0.5'"
+                .to_string(),
+        )),
+    );
+}
+
+#[test]
+fn test_exit() {
+    expect_value(
+        "1 2 exit 3",
+        Err(PileError::exit(Rc::new(ProgramSource::Stdin), (1, 1), 2)),
+    );
+}
+
 #[test]
 fn test_dotimes() {
     expect_value(
@@ -317,6 +623,47 @@ begin 200 * end
     );
 }
 
+#[test]
+fn test_shadowed_builtin() {
+    expect_value(
+        "\"plugin\" -> load load",
+        Ok(&RuntimeValue::String("plugin".to_owned())),
+    );
+    expect_value(
+        "let [load] 3 -> load load end -> f f",
+        Ok(&RuntimeValue::Number(Number::Natural(3))),
+    );
+    expect_value(
+        "begin 4 end -> find \"abc\" \"b\" find",
+        Ok(&RuntimeValue::Number(Number::Natural(4))),
+    );
+    expect_value(
+        "\"abc\" \"b\" find drop",
+        Ok(&RuntimeValue::Number(Number::Natural(1))),
+    );
+    expect_value("1 -> e e", Ok(&RuntimeValue::Number(Number::Natural(1))));
+    expect_value(
+        "let [argv] \"x\" -> argv argv end -> f f",
+        Ok(&RuntimeValue::String("x".to_owned())),
+    );
+    expect_value(
+        "use \"std/math\" [max] 1.5 2 max",
+        Ok(&RuntimeValue::Number(Number::Float(2.0))),
+    );
+    expect_value(
+        "use \"std/math\" as m -7 integer m.abs 3 m.min",
+        Ok(&RuntimeValue::Number(Number::Integer(3))),
+    );
+    expect_value(
+        "use \"std/math\" [clamp] -2.5 0 10 clamp",
+        Ok(&RuntimeValue::Number(Number::Float(0.0))),
+    );
+    expect_value(
+        "use \"std/string\" [join] \"a\" \"b\" 2 \"-\" join",
+        Ok(&RuntimeValue::String("a-b".to_owned())),
+    );
+}
+
 #[test]
 fn test_alias_assign() {
     expect_value(
@@ -396,6 +743,28 @@ fn test_print() {
     expect_value("true begin x end print", Ok(&RuntimeValue::Boolean(true)));
 }
 
+#[test]
+fn test_redirected_output() {
+    let lexer = Lexer::new(
+        "\"a\" -> x x println \"oops\" eprint 2 print flush",
+        Rc::new(ProgramSource::Stdin),
+    );
+    let ast = locals::translate(Parser::new(lexer).parse().unwrap());
+    let ast = using::resolve(ast, &[]).unwrap();
+    let mut interpreter = Interpreter::new(ast, 10, true);
+    let (output, stdout, stderr) = output::test::buffered();
+    interpreter.state.output = output;
+
+    assert_eq!(interpreter.run(), Ok(None));
+    assert_eq!(
+        stdout.text(),
+        "→ \"a\"\n→ -> x\n→ x                    (= a)\n→ println\n\
+         ──── stdout ────\na\n\n────────────────\n→ \"oops\"\n→ eprint\n\
+         → 2\n→ print\n──── stdout ────\n2\n────────────────\n→ flush\n"
+    );
+    assert_eq!(stderr.text(), "oops");
+}
+
 #[test]
 fn test_showstack() {
     expect_value(
@@ -586,74 +955,196 @@ fn test_cast_to_float() {
 }
 
 #[test]
-fn test_concat() {
+fn test_cast_strings() {
     expect_value(
-        "\"hello\" \"_world\" concat",
-        Ok(&RuntimeValue::String("hello_world".to_owned())),
+        r#" "42" natural"#,
+        Ok(&RuntimeValue::Number(Number::Natural(42))),
     );
     expect_value(
-        "\"a\" \"b\" \"c\" concat concat",
-        Ok(&RuntimeValue::String("abc".to_owned())),
+        r#" " 3.7\n" natural"#,
+        Ok(&RuntimeValue::Number(Number::Natural(3))),
     );
-    expect_value("\"\" \"\" concat", Ok(&RuntimeValue::String("".to_owned())));
-}
-
-#[test]
-fn test_length() {
     expect_value(
-        "\"some_string\" length",
-        Ok(&RuntimeValue::Number(Number::Natural(11))),
+        r#" "-12" integer"#,
+        Ok(&RuntimeValue::Number(Number::Integer(-12))),
     );
-    expect_value("\"\" length", Ok(&RuntimeValue::Number(Number::Natural(0))));
     expect_value(
-        "\"not_consumed\" length drop",
-        Ok(&RuntimeValue::String("not_consumed".to_owned())),
+        r#" "+7" integer"#,
+        Ok(&RuntimeValue::Number(Number::Integer(7))),
     );
     expect_value(
-        "\"ünicöde\" length",
-        Ok(&RuntimeValue::Number(Number::Natural(7))),
+        r#" "1e3" float"#,
+        Ok(&RuntimeValue::Number(Number::Float(1000.0))),
     );
-}
-
-#[test]
-fn test_contains() {
     expect_value(
-        "\"string\" \"s\" contains",
-        Ok(&RuntimeValue::Boolean(true)),
+        r#" "abc" float"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "'abc' isn't a number".to_owned(),
+        )),
     );
-
-    expect_value("\"\" \"str\" contains", Ok(&RuntimeValue::Boolean(false)));
-
-    expect_value("\"str\" \"\" contains", Ok(&RuntimeValue::Boolean(true)));
-
-    expect_value("\"pile\" \"pi\" contains", Ok(&RuntimeValue::Boolean(true)));
-}
-
-#[test]
-fn test_upcase() {
     expect_value(
-        "\"pile\" upcase",
-        Ok(&RuntimeValue::String("PILE".to_owned())),
+        r#" "inf" float"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "'inf' isn't a number".to_owned(),
+        )),
     );
     expect_value(
-        "\"ünicöde ß\" upcase",
-        Ok(&RuntimeValue::String("ÜNICÖDE SS".to_owned())),
+        r#" "1x" natural"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "'1x' isn't a number".to_owned(),
+        )),
     );
     expect_value(
-        "\"mIxEd_CaSe\" upcase",
-        Ok(&RuntimeValue::String("MIXED_CASE".to_owned())),
+        r#" "-5" natural"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from integer '-5' to natural is invalid".to_owned(),
+        )),
     );
-}
-
-#[test]
-fn test_downcase() {
     expect_value(
-        "\"PILE\" downcase",
-        Ok(&RuntimeValue::String("pile".to_owned())),
+        r#" "99999999999999999999" natural"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from bigint '99999999999999999999' to natural is \
+             invalid"
+                .to_owned(),
+        )),
     );
     expect_value(
-        "\"ÜNICÖDE SS\" downcase",
-        Ok(&RuntimeValue::String("ünicöde ss".to_owned())),
+        "true natural",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected number or string found boolean 'true'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_cast_boolean() {
+    expect_value(r#" "true" boolean"#, Ok(&RuntimeValue::Boolean(true)));
+    expect_value(r#" " false " boolean"#, Ok(&RuntimeValue::Boolean(false)));
+    expect_value("false boolean", Ok(&RuntimeValue::Boolean(false)));
+    expect_value(
+        r#" "yes" boolean"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "'yes' isn't a boolean".to_owned(),
+        )),
+    );
+    expect_value(
+        "1 boolean",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected boolean or string found natural '1'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_tostring() {
+    expect_value("42 tostring", Ok(&RuntimeValue::String("42".to_owned())));
+    expect_value(
+        "-1.5 tostring",
+        Ok(&RuntimeValue::String("-1.5".to_owned())),
+    );
+    expect_value(
+        "true tostring",
+        Ok(&RuntimeValue::String("true".to_owned())),
+    );
+    expect_value(
+        r#" "text" tostring"#,
+        Ok(&RuntimeValue::String("text".to_owned())),
+    );
+    expect_value(
+        "12345 tostring length",
+        Ok(&RuntimeValue::Number(Number::Natural(5))),
+    );
+    expect_value(
+        "-42 tostring integer",
+        Ok(&RuntimeValue::Number(Number::Integer(-42))),
+    );
+}
+
+#[test]
+fn test_concat() {
+    expect_value(
+        "\"hello\" \"_world\" concat",
+        Ok(&RuntimeValue::String("hello_world".to_owned())),
+    );
+    expect_value(
+        "\"a\" \"b\" \"c\" concat concat",
+        Ok(&RuntimeValue::String("abc".to_owned())),
+    );
+    expect_value("\"\" \"\" concat", Ok(&RuntimeValue::String("".to_owned())));
+}
+
+#[test]
+fn test_length() {
+    expect_value(
+        "\"some_string\" length",
+        Ok(&RuntimeValue::Number(Number::Natural(11))),
+    );
+    expect_value("\"\" length", Ok(&RuntimeValue::Number(Number::Natural(0))));
+    expect_value(
+        "\"not_consumed\" length drop",
+        Ok(&RuntimeValue::String("not_consumed".to_owned())),
+    );
+    expect_value(
+        "\"ünicöde\" length",
+        Ok(&RuntimeValue::Number(Number::Natural(7))),
+    );
+}
+
+#[test]
+fn test_contains() {
+    expect_value(
+        "\"string\" \"s\" contains",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+
+    expect_value("\"\" \"str\" contains", Ok(&RuntimeValue::Boolean(false)));
+
+    expect_value("\"str\" \"\" contains", Ok(&RuntimeValue::Boolean(true)));
+
+    expect_value("\"pile\" \"pi\" contains", Ok(&RuntimeValue::Boolean(true)));
+}
+
+#[test]
+fn test_upcase() {
+    expect_value(
+        "\"pile\" upcase",
+        Ok(&RuntimeValue::String("PILE".to_owned())),
+    );
+    expect_value(
+        "\"ünicöde ß\" upcase",
+        Ok(&RuntimeValue::String("ÜNICÖDE SS".to_owned())),
+    );
+    expect_value(
+        "\"mIxEd_CaSe\" upcase",
+        Ok(&RuntimeValue::String("MIXED_CASE".to_owned())),
+    );
+}
+
+#[test]
+fn test_downcase() {
+    expect_value(
+        "\"PILE\" downcase",
+        Ok(&RuntimeValue::String("pile".to_owned())),
+    );
+    expect_value(
+        "\"ÜNICÖDE SS\" downcase",
+        Ok(&RuntimeValue::String("ünicöde ss".to_owned())),
     );
     expect_value(
         "\"mIxEd_CaSe\" downcase",
@@ -759,6 +1250,227 @@ fn test_index() {
     );
 }
 
+#[test]
+fn test_split_join() {
+    expect_value(
+        r#" "a,b,,c" "," split"#,
+        Ok(&RuntimeValue::Number(Number::Natural(4))),
+    );
+    expect_value(
+        r#" "a,b,,c" "," split "|" join"#,
+        Ok(&RuntimeValue::String("a|b||c".to_owned())),
+    );
+    expect_value(
+        r#" "äbc" "" split "-" join"#,
+        Ok(&RuntimeValue::String("ä-b-c".to_owned())),
+    );
+    expect_value(
+        r#" "" "," split"#,
+        Ok(&RuntimeValue::Number(Number::Natural(1))),
+    );
+    expect_value(r#" 0 ", " join"#, Ok(&RuntimeValue::String("".to_owned())));
+    expect_value(
+        r#" "a" 2 ", " join"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Stack underflow".to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "a" 1 2 ", " join"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected string found natural '1'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_replace() {
+    expect_value(
+        r#" "a-b-c" "-" "+" replace"#,
+        Ok(&RuntimeValue::String("a+b+c".to_owned())),
+    );
+    expect_value(
+        r#" "größe" "ö" "oe" replace"#,
+        Ok(&RuntimeValue::String("groeße".to_owned())),
+    );
+    expect_value(
+        r#" "abc" "x" "y" replace"#,
+        Ok(&RuntimeValue::String("abc".to_owned())),
+    );
+}
+
+#[test]
+fn test_substring() {
+    expect_value(
+        r#" "hello" 1 4 substring"#,
+        Ok(&RuntimeValue::String("ell".to_owned())),
+    );
+    expect_value(
+        r#" "ünicöde" 3 7 substring"#,
+        Ok(&RuntimeValue::String("cöde".to_owned())),
+    );
+    expect_value(
+        r#" "abc" 2 2 substring"#,
+        Ok(&RuntimeValue::String("".to_owned())),
+    );
+    expect_value(
+        r#" "abc" 2 4 substring"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            r#"Invalid range 2..4 for string "abc""#.to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "abc" 2 1 substring"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            r#"Invalid range 2..1 for string "abc""#.to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "abc" -1 2 substring"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't use integer '-1' as string index".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_startswith_endswith() {
+    expect_value(
+        r#" "hello" "he" startswith"#,
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        r#" "hello" "lo" startswith"#,
+        Ok(&RuntimeValue::Boolean(false)),
+    );
+    expect_value(
+        r#" "hello" "lo" endswith"#,
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(r#" "hello" "" endswith"#, Ok(&RuntimeValue::Boolean(true)));
+}
+
+#[test]
+fn test_find() {
+    expect_value(r#" "ünicöde" "öd" find"#, Ok(&RuntimeValue::Boolean(true)));
+    expect_value(
+        r#" "ünicöde" "öd" find drop"#,
+        Ok(&RuntimeValue::Number(Number::Natural(4))),
+    );
+    expect_value(r#" "hello" "x" find"#, Ok(&RuntimeValue::Boolean(false)));
+    expect_value(
+        r#" "hello" "x" find drop"#,
+        Ok(&RuntimeValue::Number(Number::Natural(0))),
+    );
+}
+
+#[test]
+fn test_repeat_reverse() {
+    expect_value(
+        r#" "ab" 3 repeat"#,
+        Ok(&RuntimeValue::String("ababab".to_owned())),
+    );
+    expect_value(
+        r#" "ab" 0 repeat"#,
+        Ok(&RuntimeValue::String("".to_owned())),
+    );
+    expect_value(
+        r#" "ab" 18446744073709551615 repeat"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            r#"Can't repeat "ab" 18446744073709551615 times"#.to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "ünicöde" reverse"#,
+        Ok(&RuntimeValue::String("edöcinü".to_owned())),
+    );
+}
+
+#[test]
+fn test_ord_chr() {
+    expect_value(
+        r#" "A" ord"#,
+        Ok(&RuntimeValue::Number(Number::Natural(65))),
+    );
+    expect_value(
+        r#" "ö" ord"#,
+        Ok(&RuntimeValue::Number(Number::Natural(246))),
+    );
+    expect_value(r#" 128512 chr"#, Ok(&RuntimeValue::String("😀".to_owned())));
+    expect_value(
+        r#" "ab" ord"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            r#"Expected a single char found "ab""#.to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "" ord"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            r#"Expected a single char found """#.to_owned(),
+        )),
+    );
+    expect_value(
+        r#" 55296 chr"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Invalid code point 55296".to_owned(),
+        )),
+    );
+    expect_value(
+        r#" 1.5 chr"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't use float '1.5' as code point".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_regex() {
+    expect_value(
+        r#" "id=42" "^id=[0-9]+$" rematch"#,
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        r#" "id=x" "[0-9]+" rematch"#,
+        Ok(&RuntimeValue::Boolean(false)),
+    );
+    expect_value(
+        r#" "größe 12 und 345" "[0-9]+" refind drop"#,
+        Ok(&RuntimeValue::String("12".to_owned())),
+    );
+    expect_value(
+        r#" "none" "[0-9]+" refind"#,
+        Ok(&RuntimeValue::Boolean(false)),
+    );
+    expect_value(
+        r#" "a=1 b=2" "(\\w)=(?P<n>\\d)" "${n}:$1" rereplace"#,
+        Ok(&RuntimeValue::String("1:a 2:b".to_owned())),
+    );
+    expect_value(
+        r#" "key: value" "(\\w+): (\\w+)" recaptures"#,
+        Ok(&RuntimeValue::Number(Number::Natural(3))),
+    );
+}
+
 #[test]
 fn test_let1() {
     expect_value(
@@ -829,7 +1541,7 @@ f2
 
 #[test]
 fn test_numeric_overflow() {
-    expect_value(
+    expect_strict_value(
         "18446744073709551615 1 +",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -838,7 +1550,7 @@ fn test_numeric_overflow() {
                 .to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "0 1 -",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -846,7 +1558,7 @@ fn test_numeric_overflow() {
             "Numeric overflow while subtracting '0' and '1'".to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "100000000000 1000000000 *",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -855,7 +1567,7 @@ fn test_numeric_overflow() {
                 .to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "-9000000000000000005 -9000000000000000005 +",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -865,7 +1577,7 @@ fn test_numeric_overflow() {
                 .to_string(),
         )),
     );
-    expect_value(
+    expect_strict_value(
         "-200000000000005 -200000000000005 *",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -877,6 +1589,386 @@ fn test_numeric_overflow() {
     );
 }
 
+#[test]
+fn test_overflow_promotion() {
+    expect_value(
+        "18446744073709551615 1 +",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "18446744073709551616".parse().unwrap(),
+        ))),
+    );
+    expect_value("0 1 -", Ok(&RuntimeValue::Number(Number::Integer(-1))));
+    expect_value(
+        "100000000000 1000000000 *",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "100000000000000000000".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "-9000000000000000005 -9000000000000000005 +",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "-18000000000000000010".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "-9223372036854775808 -1 /",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "9223372036854775808".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "3 100 pow 3 99 pow /",
+        Ok(&RuntimeValue::Number(Number::BigInt(lex::BigInt::from(
+            3u64,
+        )))),
+    );
+    expect_value(
+        "10 0 /",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Division by zero while dividing '10' and '0'".to_owned(),
+        )),
+    );
+    expect_value(
+        "2 -1 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't raise natural '2' to the negative power '-1'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_bigint() {
+    expect_value(
+        "123456789012345678901234567890 987654321 *",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "121932631124828532112482853211126352690".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "100000000000000000000 7 %",
+        Ok(&RuntimeValue::Number(Number::BigInt("2".parse().unwrap()))),
+    );
+    expect_value(
+        "-100000000000000000000 7 mod",
+        Ok(&RuntimeValue::Number(Number::BigInt("5".parse().unwrap()))),
+    );
+    expect_value(
+        "-100000000000000000000 abs",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "100000000000000000000".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "5 bigint 3 integer -",
+        Ok(&RuntimeValue::Number(Number::BigInt("2".parse().unwrap()))),
+    );
+    expect_value(
+        "100000000000000000000 -5 min",
+        Ok(&RuntimeValue::Number(Number::BigInt("-5".parse().unwrap()))),
+    );
+    expect_value(
+        "100000000000000000000 99999999999999999999 >",
+        Ok(&RuntimeValue::Boolean(true)),
+    );
+    expect_value(
+        "2 bigint 0.5 +",
+        Ok(&RuntimeValue::Number(Number::Float(2.5))),
+    );
+    expect_value(
+        "100000000000000000000 0.5 +",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't convert bigint '100000000000000000000' to float without \
+             losing precision"
+                .to_owned(),
+        )),
+    );
+    expect_value(
+        "2 bigint -1 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't raise bigint '2' to the negative power '-1'".to_owned(),
+        )),
+    );
+    expect_value(
+        "2 bigint 5000000000 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric overflow while raising '2' to '5000000000'".to_owned(),
+        )),
+    );
+    expect_strict_value(
+        "2 bigint 1 +",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric type mismatch: bigint '2', natural '1'".to_owned(),
+        )),
+    );
+}
+
+#[test]
+fn test_rational() {
+    expect_value(
+        "1/2 1/3 +",
+        Ok(&RuntimeValue::Number(lex::parse_number("5/6").unwrap())),
+    );
+    expect_value(
+        "1/2 1/3 -",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/6").unwrap())),
+    );
+    expect_value(
+        "2/3 3/4 *",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/2").unwrap())),
+    );
+    expect_value(
+        "1 rational 3 /",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/3").unwrap())),
+    );
+    expect_value(
+        "-6/4",
+        Ok(&RuntimeValue::Number(lex::parse_number("-3/2").unwrap())),
+    );
+    expect_value(
+        "7/2 2 %",
+        Ok(&RuntimeValue::Number(lex::parse_number("3/2").unwrap())),
+    );
+    expect_value(
+        "-7/2 2 mod",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/2").unwrap())),
+    );
+    expect_value(
+        "2/3 -2 pow",
+        Ok(&RuntimeValue::Number(lex::parse_number("9/4").unwrap())),
+    );
+    expect_value("1/2 1 <", Ok(&RuntimeValue::Boolean(true)));
+    expect_value("2/4 1/2 =", Ok(&RuntimeValue::Boolean(true)));
+    expect_value("1/4 0.5 +", Ok(&RuntimeValue::Number(Number::Float(0.75))));
+    expect_value(
+        "-5/2 round",
+        Ok(&RuntimeValue::Number(lex::parse_number("-3/1").unwrap())),
+    );
+    expect_value(
+        "-5/2 floor",
+        Ok(&RuntimeValue::Number(lex::parse_number("-3/1").unwrap())),
+    );
+    expect_value(
+        "-5/2 ceil",
+        Ok(&RuntimeValue::Number(lex::parse_number("-2/1").unwrap())),
+    );
+    expect_value(
+        "-5/2 trunc",
+        Ok(&RuntimeValue::Number(lex::parse_number("-2/1").unwrap())),
+    );
+    expect_value(
+        "7/2 floor",
+        Ok(&RuntimeValue::Number(lex::parse_number("3/1").unwrap())),
+    );
+    expect_value(
+        "-7/2 round",
+        Ok(&RuntimeValue::Number(lex::parse_number("-4/1").unwrap())),
+    );
+    expect_value(
+        "1/2 1/2 +",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/1").unwrap())),
+    );
+    expect_value(
+        "1/2 1/2 -",
+        Ok(&RuntimeValue::Number(lex::parse_number("0/1").unwrap())),
+    );
+    expect_value(
+        "-1/2 1/2 -",
+        Ok(&RuntimeValue::Number(lex::parse_number("-1/1").unwrap())),
+    );
+    expect_value(
+        "100000000000000000001/2 2 *",
+        Ok(&RuntimeValue::Number(
+            lex::parse_number("100000000000000000001/1").unwrap(),
+        )),
+    );
+    expect_value(
+        "1 1/2 max",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/1").unwrap())),
+    );
+    expect_value("4/2 tostring", Ok(&RuntimeValue::String("2".to_owned())));
+    expect_value("0/3 tostring", Ok(&RuntimeValue::String("0".to_owned())));
+    expect_value(
+        "\"4/2\" rational tostring",
+        Ok(&RuntimeValue::String("2".to_owned())),
+    );
+    expect_value(
+        "1/2 1/2 + tostring",
+        Ok(&RuntimeValue::String("1".to_owned())),
+    );
+    expect_value(
+        "-5/2 abs",
+        Ok(&RuntimeValue::Number(lex::parse_number("5/2").unwrap())),
+    );
+    expect_value(
+        "1/3 1/4 max",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/3").unwrap())),
+    );
+    expect_value("1/4 sqrt", Ok(&RuntimeValue::Number(Number::Float(0.5))));
+    expect_value(
+        "1/2 0 /",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Division by zero while dividing '1/2' and '0'".to_owned(),
+        )),
+    );
+    expect_value(
+        "0/1 -1 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Division by zero while raising '0' to '-1'".to_owned(),
+        )),
+    );
+    expect_value(
+        "4 1/2 pow",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Can't raise natural '4' to the fractional power '1/2'".to_owned(),
+        )),
+    );
+    expect_strict_value(
+        "1/2 1 +",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Numeric type mismatch: rational '1/2', natural '1'".to_owned(),
+        )),
+    );
+    expect_strict_value(
+        "1/2 1/2 +",
+        Ok(&RuntimeValue::Number(lex::parse_number("1/1").unwrap())),
+    );
+}
+
+#[test]
+fn test_cast_bigint_rational() {
+    expect_value(
+        "5 bigint",
+        Ok(&RuntimeValue::Number(Number::BigInt("5".parse().unwrap()))),
+    );
+    expect_value(
+        "-5 bigint",
+        Ok(&RuntimeValue::Number(Number::BigInt("-5".parse().unwrap()))),
+    );
+    expect_value(
+        "-7/2 bigint",
+        Ok(&RuntimeValue::Number(Number::BigInt("-3".parse().unwrap()))),
+    );
+    expect_value(
+        "1e20 bigint",
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "100000000000000000000".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        r#" " 12345678901234567890123 " bigint"#,
+        Ok(&RuntimeValue::Number(Number::BigInt(
+            "12345678901234567890123".parse().unwrap(),
+        ))),
+    );
+    expect_value(
+        "0.75 rational",
+        Ok(&RuntimeValue::Number(lex::parse_number("3/4").unwrap())),
+    );
+    expect_value(
+        "-3 rational",
+        Ok(&RuntimeValue::Number(lex::parse_number("-3/1").unwrap())),
+    );
+    expect_value(
+        r#" "2/6" rational"#,
+        Ok(&RuntimeValue::Number(lex::parse_number("1/3").unwrap())),
+    );
+    expect_value(
+        "100000000000000000000 rational",
+        Ok(&RuntimeValue::Number(
+            lex::parse_number("100000000000000000000/1").unwrap(),
+        )),
+    );
+    expect_value("7/2 natural", Ok(&RuntimeValue::Number(Number::Natural(3))));
+    expect_value(
+        "-7/2 integer",
+        Ok(&RuntimeValue::Number(Number::Integer(-3))),
+    );
+    expect_value("1/4 float", Ok(&RuntimeValue::Number(Number::Float(0.25))));
+    expect_value(
+        "100000000000000000000 float",
+        Ok(&RuntimeValue::Number(Number::Float(1e20))),
+    );
+    expect_value(
+        "5 bigint natural",
+        Ok(&RuntimeValue::Number(Number::Natural(5))),
+    );
+    expect_value(
+        "-5 bigint integer",
+        Ok(&RuntimeValue::Number(Number::Integer(-5))),
+    );
+    expect_value(
+        "-5 bigint natural",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from bigint '-5' to natural is invalid".to_owned(),
+        )),
+    );
+    expect_value(
+        "100000000000000000000 integer",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from bigint '100000000000000000000' to integer is \
+             invalid"
+                .to_owned(),
+        )),
+    );
+    expect_value(
+        "-1/2 -1/2 * 10000000000000000000 * natural",
+        Ok(&RuntimeValue::Number(Number::Natural(2500000000000000000))),
+    );
+    expect_value(
+        "1.0 0.0 / bigint",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from float 'inf' to bigint is invalid".to_owned(),
+        )),
+    );
+    expect_value(
+        "0.0 0.0 / rational",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Conversion from float 'NaN' to rational is invalid".to_owned(),
+        )),
+    );
+    expect_value(
+        r#" "1/0" rational"#,
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "'1/0' has a zero denominator".to_owned(),
+        )),
+    );
+    expect_value(
+        "100000000000000000000 tostring",
+        Ok(&RuntimeValue::String("100000000000000000000".to_owned())),
+    );
+    expect_value("3/6 tostring", Ok(&RuntimeValue::String("1/2".to_owned())));
+}
+
 #[test]
 fn test_div_by_zero() {
     expect_value(
@@ -908,7 +2000,7 @@ fn test_type_errors() {
         )),
     );
 
-    expect_value(
+    expect_strict_value(
         "12.34 4 +",
         Err(PileError::in_line(
             Rc::new(ProgramSource::Stdin),
@@ -1014,7 +2106,7 @@ fn read(input: &str) -> ResolvedAst {
         .parse()
         .unwrap();
     let ast = locals::translate(ast);
-    using::resolve(ast).unwrap()
+    using::resolve(ast, &[]).unwrap()
 }
 
 #[test]
@@ -1065,3 +2157,21 @@ fn test_eval_cleanup_vars() -> Result<(), PileError> {
 
     Ok(())
 }
+
+#[test]
+fn test_snapshot() {
+    let prog = "2 -> two \"x\" -> x begin two * end -> double 3.5 true";
+    let lexer = Lexer::new(prog, Rc::new(ProgramSource::Stdin));
+    let ast = locals::translate(Parser::new(lexer).parse().unwrap());
+    let mut interpreter =
+        Interpreter::new(using::resolve(ast, &[]).unwrap(), 10, false);
+    interpreter.run().unwrap();
+
+    assert_eq!(
+        interpreter.snapshot(),
+        Ok(
+            "begin two * end -> double\n2 -> two\n\"x\" -> x\n3.5\ntrue\n"
+                .to_owned()
+        )
+    );
+}
@@ -2,9 +2,9 @@ use super::scoping::ScopeStack;
 use crate::lex::Operator;
 use crate::lex::Token;
 use crate::parse::Expr;
-use crate::program_source::ProgramSource;
 
 use std::fmt;
+use std::io::Write;
 
 struct TracedExpr<'e>(&'e Expr);
 
@@ -20,12 +20,8 @@ impl<'e> fmt::Display for TracedExpr<'e> {
                 }
                 write!(f, " end")
             }
-            Expr::Use { subprogram, .. } => {
-                let source_file = match subprogram.source.as_ref() {
-                    ProgramSource::File(path) => path.to_string_lossy(),
-                    _ => panic!("impossible use statement"),
-                };
-                write!(f, "use \"{}\"", source_file)
+            Expr::Use { file, import, .. } => {
+                write!(f, "use \"{}\"{}", file.to_string_lossy(), import)
             }
             Expr::Save { var, .. } => write!(f, "save(\"{}\")", var),
             Expr::Restore { var, .. } => write!(f, "restore(\"{}\")", var),
@@ -33,38 +29,68 @@ impl<'e> fmt::Display for TracedExpr<'e> {
     }
 }
 
-pub fn before_eval(expr: &Expr, lookup: &ScopeStack) {
-    if let Expr::Atom {
-        token: Token::Identifier(ident),
-        ..
-    } = expr
-    {
-        if let Some(value) = lookup.resolve(ident) {
-            println!("→ {:20} (= {})", ident, value)
-        }
-    } else {
-        println!("→ {}", TracedExpr(expr));
+// NOTE: Tracing is best effort, so write errors are ignored.
+pub fn before_eval(expr: &Expr, lookup: &ScopeStack, out: &mut dyn Write) {
+    match expr {
+        Expr::Atom {
+            token: Token::Identifier(ident),
+            ..
+        } => match lookup.resolve(ident) {
+            Some(value) => {
+                let _ = writeln!(out, "→ {:20} (= {})", ident, value);
+            }
+            None if Operator::from_name(ident).is_some() => {
+                let _ = writeln!(out, "→ {}", ident);
 
-        if is_print(expr) {
-            println!("──── stdout ────")
+                if calls_print(expr, lookup) {
+                    let _ = writeln!(out, "──── stdout ────");
+                }
+            }
+            None => (),
+        },
+        expr => {
+            let _ = writeln!(out, "→ {}", TracedExpr(expr));
+
+            if calls_print(expr, lookup) {
+                let _ = writeln!(out, "──── stdout ────");
+            }
         }
     }
 }
 
-pub fn after_eval(expr: &Expr) {
-    if is_print(expr) {
-        println!("\n────────────────")
+pub fn after_eval(expr: &Expr, lookup: &ScopeStack, out: &mut dyn Write) {
+    if calls_print(expr, lookup) {
+        let _ = writeln!(out, "\n────────────────");
     }
 }
 
 fn is_print(expr: &Expr) -> bool {
-    matches!(
-        expr,
+    match expr {
         Expr::Atom {
-            token: Token::Operator(Operator::Print),
+            token: Token::Operator(op),
             ..
+        } => prints(*op),
+        _ => false,
+    }
+}
+
+fn prints(op: Operator) -> bool {
+    matches!(op, Operator::Print | Operator::Println)
+}
+
+// NOTE: Operators that aren't keywords are identifiers, which only print if
+// nothing is bound to them.
+fn calls_print(expr: &Expr, lookup: &ScopeStack) -> bool {
+    match expr {
+        Expr::Atom {
+            token: Token::Identifier(ident),
+            ..
+        } => {
+            lookup.resolve(ident).is_none()
+                && Operator::from_name(ident).is_some_and(prints)
         }
-    )
+        expr => is_print(expr),
+    }
 }
 
 #[cfg(test)]
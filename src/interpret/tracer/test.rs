@@ -1,6 +1,9 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use super::*;
+use crate::interpret::RuntimeValue;
 use crate::lex::{Number, Operator, Token};
-use crate::parse::{Ast, Expr};
+use crate::parse::{Ast, Expr, Import};
 use crate::program_source::ProgramSource;
 
 use std::path::PathBuf;
@@ -41,6 +44,19 @@ fn test_is_print() {
     );
 }
 
+#[test]
+fn test_calls_print() {
+    let println = Expr::Atom {
+        token: Token::Identifier("println".to_owned()),
+        line: 1,
+    };
+    let mut lookup = ScopeStack::new();
+    assert_eq!(calls_print(&println, &lookup), true);
+
+    lookup.assign("println", RuntimeValue::Boolean(true));
+    assert_eq!(calls_print(&println, &lookup), false);
+}
+
 #[test]
 fn test_fmt_traced_expr() {
     assert_eq!(
@@ -127,6 +143,7 @@ fn test_fmt_traced_expr() {
         format!(
             "{}",
             TracedExpr(&Expr::Use {
+                file: PathBuf::from("other_file.pile"),
                 subprogram: Ast {
                     source: Rc::new(ProgramSource::File(PathBuf::from(
                         "other_file.pile"
@@ -134,6 +151,7 @@ fn test_fmt_traced_expr() {
                     expressions: vec![],
                 },
                 line: 1,
+                import: Import::All,
             })
         ),
         "use \"other_file.pile\""
@@ -7,10 +7,19 @@ use std::iter::Peekable;
 use std::rc::Rc;
 use std::str::Chars;
 
+mod bigint;
+mod operator;
+mod rational;
+pub use bigint::BigInt;
+pub use operator::{Operator, OperatorInfo, OPERATORS};
+pub use rational::Rational;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Number {
     Natural(u64),
     Integer(i64),
+    BigInt(BigInt),
+    Rational(Rational),
     Float(f64),
 }
 
@@ -19,105 +28,106 @@ impl fmt::Display for Number {
         match self {
             Number::Natural(n) => write!(f, "{}", n),
             Number::Integer(n) => write!(f, "{}", n),
+            Number::BigInt(n) => write!(f, "{}", n),
+            Number::Rational(n) => write!(f, "{}", n),
             Number::Float(n) => write!(f, "{}", n),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum Operator {
-    // control flow
-    If,
-    Dotimes,
-    While,
-    // arithmetic
-    Plus,
-    Minus,
-    Div,
-    Mul,
-    // predicates
-    Greater,
-    GreaterEqual,
-    Equal,
-    LessEqual,
-    Less,
-    And,
-    Or,
-    Not,
-    // builtins
-    Print,
-    Showstack,
-    Assert,
-    Dup,
-    Drop,
-    Swap,
-    Pick,
-    Clear,
-    StackSize,
-    ReadLines,
-    // casts
-    Natural,
-    Integer,
-    Float,
-    // string operators
-    Concat,
-    Length,
-    Contains,
-    Upcase,
-    Downcase,
-    Trim,
-    Format,
-    Index,
+impl Number {
+    pub fn type_fmt(&self) -> String {
+        let name = match self {
+            Number::Natural(_) => "natural",
+            Number::Integer(_) => "integer",
+            Number::BigInt(_) => "bigint",
+            Number::Rational(_) => "rational",
+            Number::Float(_) => "float",
+        };
+
+        format!("{} '{}'", name, self)
+    }
+
+    pub fn source_fmt(&self) -> String {
+        match self {
+            Number::Natural(n) => n.to_string(),
+            Number::Integer(i) if *i < 0 => i.to_string(),
+            Number::Integer(i) => format!("{} integer", i),
+            // NOTE: Small bigints would be read back as naturals or integers.
+            Number::BigInt(b)
+                if b.to_i64().is_some() || b.to_u64().is_some() =>
+            {
+                format!("{} bigint", b)
+            }
+            Number::BigInt(b) => b.to_string(),
+            Number::Rational(r) => format!("{}/{}", r.numer(), r.denom()),
+            Number::Float(fl) if fl.is_nan() => "0.0 0.0 /".to_owned(),
+            Number::Float(fl) if fl.is_infinite() => {
+                format!("{:?} 0.0 /", fl.signum())
+            }
+            Number::Float(fl) => format!("{:?}", fl),
+        }
+    }
 }
 
-impl fmt::Display for Operator {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Operator::If => "if",
-                Operator::Dotimes => "dotimes",
-                Operator::While => "while",
-                Operator::Plus => "+",
-                Operator::Minus => "-",
-                Operator::Div => "/",
-                Operator::Mul => "*",
-                Operator::Greater => ">",
-                Operator::GreaterEqual => ">=",
-                Operator::Equal => "=",
-                Operator::LessEqual => "<=",
-                Operator::And => "and",
-                Operator::Or => "or",
-                Operator::Not => "not",
-                Operator::Less => "<",
-                Operator::Print => "print",
-                Operator::Showstack => "showstack",
-                Operator::ReadLines => "readlines",
-                Operator::Assert => "assert",
-                Operator::Dup => "dup",
-                Operator::Drop => "drop",
-                Operator::Swap => "swap",
-                Operator::Pick => "pick",
-                Operator::Clear => "clear",
-                Operator::StackSize => "stacksize",
-                Operator::Natural => "natural",
-                Operator::Integer => "integer",
-                Operator::Float => "float",
-                Operator::Concat => "concat",
-                Operator::Length => "length",
-                Operator::Contains => "contains",
-                Operator::Downcase => "downcase",
-                Operator::Upcase => "upcase",
-                Operator::Trim => "trim",
-                Operator::Format => "format",
-                Operator::Index => "index",
+pub fn string_source_fmt(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+
+    escaped.push('"');
+    for c in string.chars() {
+        match c {
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            '\0' => escaped.push_str("\\0"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+// NOTE: Digits with an optional '+' are naturals and with a '-' integers.
+// Both become bigints if they don't fit. Two such numbers separated by a '/'
+// are a rational, everything else that contains a digit has to be a float.
+pub fn parse_number(s: &str) -> Result<Number, String> {
+    let digits_only = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let is_whole = |s: &str| {
+        let digits = s.strip_prefix(&['-', '+'][..]).unwrap_or(s);
+        !digits.is_empty() && digits_only(digits)
+    };
+
+    if !s.chars().any(|c| c.is_ascii_digit()) {
+        Err(format!("'{}' isn't a number", s))
+    } else if digits_only(s) || s.starts_with('+') && digits_only(&s[1..]) {
+        s.parse()
+            .map(Number::Natural)
+            .or_else(|_| s.parse().map(Number::BigInt))
+    } else if s.starts_with('-') && digits_only(&s[1..]) {
+        s.parse()
+            .map(Number::Integer)
+            .or_else(|_| s.parse().map(Number::BigInt))
+    } else {
+        match s.split_once('/') {
+            Some((numer, denom))
+                if is_whole(numer) && is_whole(denom) && digits_only(denom) =>
+            {
+                Rational::new(numer.parse()?, denom.parse()?)
+                    .map(Number::Rational)
+                    .ok_or_else(|| format!("'{}' has a zero denominator", s))
             }
-        )
+            _ => s
+                .parse()
+                .map(Number::Float)
+                .map_err(|_| format!("'{}' isn't a number", s)),
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Token {
     // keywords
     Begin,
@@ -134,16 +144,23 @@ pub enum Token {
     Boolean(bool),
     // use
     Use,
+    As,
     // comments
     Comment,
 }
 
 impl Token {
+    pub fn source_fmt(&self) -> String {
+        match self {
+            Token::Number(n) => n.source_fmt(),
+            Token::String(s) => string_source_fmt(s),
+            token => token.to_string(),
+        }
+    }
+
     pub fn error_fmt(&self) -> String {
         match self {
-            Token::Number(Number::Natural(n)) => format!("natural '{}'", n),
-            Token::Number(Number::Integer(i)) => format!("integer '{}'", i),
-            Token::Number(Number::Float(fl)) => format!("float '{}'", fl),
+            Token::Number(n) => n.type_fmt(),
             Token::Identifier(ident) => format!("identifier '{}'", ident),
             Token::String(s) => format!("string \"{}\"", s),
             Token::Boolean(true) => "boolean 'true'".to_owned(),
@@ -156,6 +173,7 @@ impl Token {
             Token::Assign => "token '->'".to_owned(),
             Token::Operator(o) => format!("operator '{}'", o),
             Token::Use => "token 'use'".to_owned(),
+            Token::As => "token 'as'".to_owned(),
             Token::Comment => "".to_owned(),
         }
     }
@@ -174,6 +192,7 @@ impl fmt::Display for Token {
             Token::Number(n) => write!(f, "{}", n),
             Token::String(s) => write!(f, "\"{}\"", s),
             Token::Use => write!(f, "use"),
+            Token::As => write!(f, "as"),
             Token::Boolean(b) => write!(f, "{}", b),
             Token::Identifier(i) => write!(f, "{}", i),
             Token::Comment => write!(f, ""),
@@ -198,7 +217,7 @@ pub struct LexerItem {
 impl<'a> Lexer<'a> {
     const DEFAULT_CAPACITY: usize = 16;
 
-    pub fn new(text: &str, source: Rc<ProgramSource>) -> Lexer {
+    pub fn new(text: &str, source: Rc<ProgramSource>) -> Lexer<'_> {
         Lexer {
             source,
             input: text.chars().peekable(),
@@ -273,47 +292,33 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn identifier(&mut self) -> Token {
+    fn identifier(&mut self) -> Result<Token, PileError> {
         let ident = self
-            .collect_while(|c| c.is_alphanumeric() || c == '_')
+            .collect_while(|c| c.is_alphanumeric() || c == '_' || c == '.')
             .to_lowercase();
 
-        match ident.as_ref() {
+        let valid_segment = |segment: &str| {
+            segment.chars().next().is_some_and(|c| !c.is_numeric())
+        };
+        if !ident.split('.').all(valid_segment) {
+            return Err(
+                self.lex_error(&format!("Invalid qualified name '{}'", ident))
+            );
+        }
+
+        Ok(match ident.as_ref() {
             "begin" => Token::Begin,
             "end" => Token::End,
-            "if" => Token::Operator(Operator::If),
-            "dotimes" => Token::Operator(Operator::Dotimes),
-            "while" => Token::Operator(Operator::While),
             "true" => Token::Boolean(true),
             "false" => Token::Boolean(false),
-            "and" => Token::Operator(Operator::And),
-            "or" => Token::Operator(Operator::Or),
-            "not" => Token::Operator(Operator::Not),
-            "print" => Token::Operator(Operator::Print),
-            "showstack" => Token::Operator(Operator::Showstack),
-            "readlines" => Token::Operator(Operator::ReadLines),
-            "assert" => Token::Operator(Operator::Assert),
-            "dup" => Token::Operator(Operator::Dup),
-            "drop" => Token::Operator(Operator::Drop),
-            "swap" => Token::Operator(Operator::Swap),
-            "pick" => Token::Operator(Operator::Pick),
-            "clear" => Token::Operator(Operator::Clear),
-            "stacksize" => Token::Operator(Operator::StackSize),
-            "natural" => Token::Operator(Operator::Natural),
-            "integer" => Token::Operator(Operator::Integer),
-            "float" => Token::Operator(Operator::Float),
-            "concat" => Token::Operator(Operator::Concat),
-            "length" => Token::Operator(Operator::Length),
-            "contains" => Token::Operator(Operator::Contains),
-            "downcase" => Token::Operator(Operator::Downcase),
-            "upcase" => Token::Operator(Operator::Upcase),
-            "trim" => Token::Operator(Operator::Trim),
-            "format" => Token::Operator(Operator::Format),
-            "index" => Token::Operator(Operator::Index),
             "use" => Token::Use,
+            "as" => Token::As,
             "let" => Token::Let,
-            _ => Token::Identifier(ident),
-        }
+            name => match Operator::from_name(name) {
+                Some(op) if op.is_keyword() => Token::Operator(op),
+                _ => Token::Identifier(ident),
+            },
+        })
     }
 
     fn escape_char(c: char) -> Result<char, char> {
@@ -371,32 +376,9 @@ impl<'a> Lexer<'a> {
     }
 
     fn parse_number(&self, s: &str) -> Result<Token, PileError> {
-        let digits_only = |s: &str| s.chars().all(|c| c.is_digit(10));
-
-        if digits_only(s) || s.starts_with('+') && digits_only(&s[1..]) {
-            match s.parse() {
-                Ok(nat) => Ok(Token::Number(Number::Natural(nat))),
-                Err(_) => Err(self.lex_error(&format!(
-                    "'{}' is too large to be represented as a number",
-                    s
-                ))),
-            }
-        } else if s.starts_with('-') && digits_only(&s[1..]) {
-            match s.parse() {
-                Ok(int) => Ok(Token::Number(Number::Integer(int))),
-                Err(_) => Err(self.lex_error(&format!(
-                    "'{}' is too small to be represented as a number",
-                    s
-                ))),
-            }
-        } else {
-            match s.parse() {
-                Ok(float) => Ok(Token::Number(Number::Float(float))),
-                Err(_) => {
-                    Err(self.lex_error(&format!("'{}' isn't a number", s)))
-                }
-            }
-        }
+        parse_number(s)
+            .map(Token::Number)
+            .map_err(|msg| self.lex_error(&msg))
     }
 
     fn is_separating(c: char) -> bool {
@@ -414,22 +396,18 @@ impl<'a> Lexer<'a> {
         let operator = self
             .collect_while(|c| !c.is_whitespace() && !Lexer::is_separating(c));
 
-        if operator.chars().any(|c| c.is_digit(10)) {
+        if operator.chars().any(|c| c.is_ascii_digit()) {
             return self.parse_number(operator.as_ref());
         }
 
         match operator.as_ref() {
-            "+" => Ok(Token::Operator(Operator::Plus)),
-            "-" => Ok(Token::Operator(Operator::Minus)),
-            "*" => Ok(Token::Operator(Operator::Mul)),
-            "/" => Ok(Token::Operator(Operator::Div)),
-            ">" => Ok(Token::Operator(Operator::Greater)),
-            ">=" => Ok(Token::Operator(Operator::GreaterEqual)),
-            "=" => Ok(Token::Operator(Operator::Equal)),
-            "<=" => Ok(Token::Operator(Operator::LessEqual)),
-            "<" => Ok(Token::Operator(Operator::Less)),
             "->" => Ok(Token::Assign),
-            o => Err(self.lex_error(&format!("Unknown operator '{}'", o))),
+            o => match Operator::from_name(o) {
+                Some(op) => Ok(Token::Operator(op)),
+                None => {
+                    Err(self.lex_error(&format!("Unknown operator '{}'", o)))
+                }
+            },
         }
     }
 
@@ -451,8 +429,10 @@ impl<'a> Lexer<'a> {
                 }
                 '"' => self.string(),
                 '0'..='9' => self.number(),
-                '+' | '-' | '*' | '/' | '=' | '<' | '>' => self.operator(),
-                c if c.is_alphabetic() || c == '_' => Ok(self.identifier()),
+                '+' | '-' | '*' | '/' | '%' | '=' | '<' | '>' => {
+                    self.operator()
+                }
+                c if c.is_alphabetic() || c == '_' => self.identifier(),
                 '[' => {
                     self.consume();
                     Ok(Token::BracketLeft)
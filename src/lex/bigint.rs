@@ -0,0 +1,503 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const DIGIT_BITS: u32 = 32;
+
+// NOTE: The magnitude is stored as base 2^32 digits, least significant
+// first and without leading zeros. Zero has no digits and is never negative.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> BigInt {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn zero() -> BigInt {
+        BigInt::new(false, vec![])
+    }
+
+    pub fn one() -> BigInt {
+        BigInt::new(false, vec![1])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::new(false, self.digits.clone())
+    }
+
+    fn magnitude(&self) -> Option<u64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0, |acc, &digit| acc << DIGIT_BITS | digit as u64),
+        )
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.negative {
+            None
+        } else {
+            self.magnitude()
+        }
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        let magnitude = self.magnitude()?;
+
+        if !self.negative {
+            i64::try_from(magnitude).ok()
+        } else if magnitude <= i64::MIN.unsigned_abs() {
+            Some((magnitude as i64).wrapping_neg())
+        } else {
+            None
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self.digits.last() {
+            Some(top) => {
+                self.digits.len() as u32 * DIGIT_BITS - top.leading_zeros()
+            }
+            None => 0,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.digits.iter().rev().fold(0.0, |acc, &digit| {
+            acc * (1u64 << DIGIT_BITS) as f64 + digit as f64
+        });
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn checked_add(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self + other)
+    }
+
+    pub fn checked_sub(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self - other)
+    }
+
+    pub fn checked_mul(&self, other: &BigInt) -> Option<BigInt> {
+        Some(self * other)
+    }
+
+    pub fn checked_div(&self, other: &BigInt) -> Option<BigInt> {
+        self.div_rem(other).map(|(quotient, _)| quotient)
+    }
+
+    pub fn checked_rem(&self, other: &BigInt) -> Option<BigInt> {
+        self.div_rem(other).map(|(_, remainder)| remainder)
+    }
+
+    pub fn checked_rem_euclid(&self, other: &BigInt) -> Option<BigInt> {
+        self.div_rem_euclid(other).map(|(_, remainder)| remainder)
+    }
+
+    // NOTE: Like the primitive integers the quotient is rounded towards zero
+    // and the remainder has the sign of the dividend.
+    pub fn div_rem(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_digits(&self.digits, &other.digits);
+
+        Some((
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, remainder),
+        ))
+    }
+
+    pub fn div_rem_euclid(&self, other: &BigInt) -> Option<(BigInt, BigInt)> {
+        let (quotient, remainder) = self.div_rem(other)?;
+
+        if !remainder.is_negative() {
+            Some((quotient, remainder))
+        } else if other.is_negative() {
+            Some((&quotient + &BigInt::one(), &remainder - other))
+        } else {
+            Some((&quotient - &BigInt::one(), &remainder + other))
+        }
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut base = self.clone();
+        let mut result = BigInt::one();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let mut a = self.abs();
+        let mut b = other.abs();
+
+        while !b.is_zero() {
+            let (_, remainder) = div_rem_digits(&a.digits, &b.digits);
+            a = b;
+            b = BigInt::new(false, remainder);
+        }
+
+        a
+    }
+
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "Invalid radix {}", radix);
+
+        // NOTE: Divide by the largest power of the radix that fits into a
+        // digit, so every division yields several characters at once.
+        let mut chunk = radix;
+        let mut chunk_width = 1;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            chunk_width += 1;
+        }
+
+        let mut chars = vec![];
+        let mut digits = self.digits.clone();
+        while !digits.is_empty() {
+            let (quotient, mut remainder) = div_rem_digit(&digits, chunk);
+            digits = BigInt::new(false, quotient).digits;
+
+            for _ in 0..chunk_width {
+                if digits.is_empty() && remainder == 0 {
+                    break;
+                }
+                chars.push(
+                    std::char::from_digit(remainder % radix, radix)
+                        .unwrap_or('?'),
+                );
+                remainder /= radix;
+            }
+        }
+
+        if chars.is_empty() {
+            chars.push('0');
+        }
+        if self.negative {
+            chars.push('-');
+        }
+
+        chars.iter().rev().collect()
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(n: u64) -> BigInt {
+        BigInt::new(false, vec![n as u32, (n >> DIGIT_BITS) as u32])
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(i: i64) -> BigInt {
+        let magnitude = BigInt::from(i.unsigned_abs());
+        BigInt::new(i < 0, magnitude.digits)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = String;
+
+    // NOTE: Decimal digits are consumed in chunks that fit into a u64.
+    fn from_str(s: &str) -> Result<BigInt, String> {
+        const CHUNK_WIDTH: usize = 19;
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("'{}' isn't an integer", s));
+        }
+
+        let mut result = BigInt::zero();
+        for chunk in digits.as_bytes().chunks(CHUNK_WIDTH) {
+            let scale = BigInt::from(10u64.pow(chunk.len() as u32));
+            let value = chunk
+                .iter()
+                .fold(0, |acc, digit| acc * 10 + (digit - b'0') as u64);
+
+            result = &(&result * &scale) + &BigInt::from(value);
+        }
+
+        Ok(BigInt::new(negative, result.digits))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_str_radix(10))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_digits(&self.digits, &other.digits),
+            (true, true) => compare_digits(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(
+                self.negative,
+                add_digits(&self.digits, &other.digits),
+            );
+        }
+
+        match compare_digits(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::new(
+                other.negative,
+                sub_digits(&other.digits, &self.digits),
+            ),
+            _ => BigInt::new(
+                self.negative,
+                sub_digits(&self.digits, &other.digits),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_digits(&self.digits, &other.digits),
+        )
+    }
+}
+
+fn compare_digits(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0;
+
+    for (index, &digit) in long.iter().enumerate() {
+        let sum = digit as u64 + *short.get(index).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> DIGIT_BITS;
+    }
+    result.push(carry as u32);
+
+    result
+}
+
+// NOTE: Expects a >= b.
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0;
+
+    for (index, &digit) in a.iter().enumerate() {
+        let subtrahend = *b.get(index).unwrap_or(&0) as u64 + borrow;
+        let (difference, underflow) =
+            (digit as u64).overflowing_sub(subtrahend);
+        result.push(difference as u32);
+        borrow = underflow as u64;
+    }
+
+    result
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0;
+        for (j, &y) in b.iter().enumerate() {
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> DIGIT_BITS;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+
+    result
+}
+
+fn div_rem_digit(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0;
+
+    for (q, &digit) in quotient.iter_mut().zip(a).rev() {
+        let current = remainder << DIGIT_BITS | digit as u64;
+        *q = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+
+    (quotient, remainder as u32)
+}
+
+fn shift_left(digits: &[u32], shift: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(digits.len() + 1);
+    let mut carry = 0;
+
+    for &digit in digits {
+        let wide = (digit as u64) << shift | carry;
+        result.push(wide as u32);
+        carry = wide >> DIGIT_BITS;
+    }
+    result.push(carry as u32);
+
+    result
+}
+
+fn shift_right(digits: &[u32], shift: u32) -> Vec<u32> {
+    let mut result = vec![0; digits.len()];
+    let mut carry = 0;
+
+    for (r, &digit) in result.iter_mut().zip(digits).rev() {
+        let wide = carry << DIGIT_BITS | digit as u64;
+        *r = (wide >> shift) as u32;
+        carry = digit as u64 & ((1 << shift) - 1);
+    }
+
+    result
+}
+
+// NOTE: Long division from Knuth's TAOCP Vol. 2, algorithm D. The divisor
+// is shifted until its top bit is set, so the estimated quotient digits are
+// off by at most two.
+fn div_rem_digits(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_digits(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    if b.len() == 1 {
+        let (quotient, remainder) = div_rem_digit(a, b[0]);
+        return (quotient, vec![remainder]);
+    }
+
+    let base = 1u64 << DIGIT_BITS;
+    let shift = b[b.len() - 1].leading_zeros();
+    let mut b = shift_left(b, shift);
+    b.pop();
+    let mut a = shift_left(a, shift);
+
+    let n = b.len();
+    let mut quotient = vec![0; a.len() - n];
+    let top = b[n - 1] as u64;
+    let second = b[n - 2] as u64;
+
+    for j in (0..quotient.len()).rev() {
+        let numerator = (a[j + n] as u64) << DIGIT_BITS | a[j + n - 1] as u64;
+        let mut estimate = numerator / top;
+        let mut rest = numerator % top;
+
+        while estimate >= base
+            || estimate * second > (rest << DIGIT_BITS | a[j + n - 2] as u64)
+        {
+            estimate -= 1;
+            rest += top;
+            if rest >= base {
+                break;
+            }
+        }
+
+        let mut borrow = 0;
+        let mut carry = 0;
+        for (i, &digit) in b.iter().enumerate() {
+            let product = estimate * digit as u64 + carry;
+            carry = product >> DIGIT_BITS;
+            let difference = a[i + j] as i64 - borrow - (product as u32) as i64;
+            a[i + j] = difference as u32;
+            borrow = (difference < 0) as i64;
+        }
+        let difference = a[j + n] as i64 - borrow - carry as i64;
+        a[j + n] = difference as u32;
+
+        if difference < 0 {
+            estimate -= 1;
+            let mut carry = 0;
+            for (i, &digit) in b.iter().enumerate() {
+                let sum = a[i + j] as u64 + digit as u64 + carry;
+                a[i + j] = sum as u32;
+                carry = sum >> DIGIT_BITS;
+            }
+            a[j + n] = a[j + n].wrapping_add(carry as u32);
+        }
+
+        quotient[j] = estimate as u32;
+    }
+
+    (quotient, shift_right(&a[..n], shift))
+}
+
+#[cfg(test)]
+mod test;
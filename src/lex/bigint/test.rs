@@ -0,0 +1,118 @@
+use proptest::prelude::*;
+
+use super::*;
+
+fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+}
+
+fn from_i128(i: i128) -> BigInt {
+    big(&i.to_string())
+}
+
+proptest! {
+    #[test]
+    fn test_display_roundtrip(s in "-?[1-9][0-9]{0,80}") {
+        prop_assert_eq!(big(&s).to_string(), s);
+    }
+
+    #[test]
+    fn test_arithmetic(a in any::<i64>(), b in any::<i64>()) {
+        let (a, b) = (a as i128, b as i128);
+        let (x, y) = (from_i128(a), from_i128(b));
+
+        prop_assert_eq!(&x + &y, from_i128(a + b));
+        prop_assert_eq!(&x - &y, from_i128(a - b));
+        prop_assert_eq!(&x * &y, from_i128(a * b));
+        prop_assert_eq!(x.cmp(&y), a.cmp(&b));
+    }
+
+    #[test]
+    fn test_div_rem(a in (i128::MIN + 1)..i128::MAX,
+                    b in any::<i64>(),
+                    c in any::<u32>()) {
+        // NOTE: Divisors with two and three digits use the long division.
+        for b in &[b as i128, (b as i128) << 32 | c as i128] {
+            let (x, y) = (from_i128(a), from_i128(*b));
+
+            if *b == 0 {
+                prop_assert_eq!(x.div_rem(&y), None);
+            } else {
+                prop_assert_eq!(
+                    x.div_rem(&y),
+                    Some((from_i128(a / b), from_i128(a % b)))
+                );
+                prop_assert_eq!(
+                    x.div_rem_euclid(&y),
+                    Some((
+                        from_i128(a.div_euclid(*b)),
+                        from_i128(a.rem_euclid(*b))
+                    ))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_div_rem(a in "[1-9][0-9]{0,60}",
+                          b in "[1-9][0-9]{0,40}",
+                          r in "[0-9]{0,40}") {
+        let (a, b) = (big(&a), big(&b));
+        let r = big(&format!("0{}", r)).div_rem(&b).unwrap().1;
+        let n = &(&a * &b) + &r;
+
+        prop_assert_eq!(n.div_rem(&b), Some((a, r)));
+    }
+
+    #[test]
+    fn test_conversions(n in any::<u64>(), i in any::<i64>()) {
+        prop_assert_eq!(BigInt::from(n).to_u64(), Some(n));
+        prop_assert_eq!(BigInt::from(i).to_i64(), Some(i));
+        prop_assert_eq!(BigInt::from(n).to_string(), n.to_string());
+        prop_assert_eq!(BigInt::from(i).to_string(), i.to_string());
+    }
+}
+
+#[test]
+fn test_limits() {
+    let above = big("18446744073709551616");
+
+    assert_eq!(above.to_u64(), None);
+    assert_eq!((&above - &BigInt::one()).to_u64(), Some(u64::MAX));
+    assert_eq!(big("-9223372036854775808").to_i64(), Some(i64::MIN));
+    assert_eq!(big("-9223372036854775809").to_i64(), None);
+    assert_eq!(big("9223372036854775808").to_i64(), None);
+    assert_eq!(big("-1").to_u64(), None);
+}
+
+#[test]
+fn test_pow_gcd() {
+    assert_eq!(big("2").pow(100), big("1267650600228229401496703205376"));
+    assert_eq!(big("-3").pow(3), big("-27"));
+    assert_eq!(big("7").pow(0), BigInt::one());
+    assert_eq!(big("-12").gcd(&big("18")), big("6"));
+    assert_eq!(big("0").gcd(&big("-5")), big("5"));
+}
+
+#[test]
+fn test_radix() {
+    assert_eq!(big("255").to_str_radix(16), "ff");
+    assert_eq!(big("-5").to_str_radix(2), "-101");
+    assert_eq!(big("0").to_str_radix(8), "0");
+    assert_eq!(
+        big("340282366920938463463374607431768211456").to_str_radix(16),
+        "100000000000000000000000000000000"
+    );
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!(big("+42"), BigInt::from(42u64));
+    assert_eq!(big("-0"), BigInt::zero());
+    assert_eq!(big("000123"), BigInt::from(123u64));
+    assert_eq!("".parse::<BigInt>(), Err("'' isn't an integer".to_owned()));
+    assert_eq!(
+        "1.5".parse::<BigInt>(),
+        Err("'1.5' isn't an integer".to_owned())
+    );
+}
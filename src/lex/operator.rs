@@ -0,0 +1,674 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub struct OperatorInfo {
+    pub operator: Operator,
+    pub name: &'static str,
+    pub stack_effect: &'static str,
+    pub description: &'static str,
+    pub example: &'static str,
+}
+
+macro_rules! operators {
+    ($($variant: ident {
+        name: $name: literal,
+        effect: $effect: literal,
+        description: $description: literal,
+        example: $example: literal $(,)?
+    }),+ $(,)?) => {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub enum Operator {
+            $($variant,)+
+        }
+
+        // NOTE: The entries are in the same order as the variants of
+        // `Operator`. `Operator::info` relies on this.
+        pub const OPERATORS: &[OperatorInfo] = &[
+            $(OperatorInfo {
+                operator: Operator::$variant,
+                name: $name,
+                stack_effect: $effect,
+                description: $description,
+                example: $example,
+            },)+
+        ];
+    };
+}
+
+operators! {
+    // control flow
+    If {
+        name: "if",
+        effect: "( then else condition -- ... )",
+        description: "Calls 'then' if 'condition' is true, otherwise 'else'.",
+        example: "begin 1 end begin 2 end true if",
+    },
+    Dotimes {
+        name: "dotimes",
+        effect: "( body n -- ... )",
+        description: "Calls 'body' n times.",
+        example: "0 begin 2 + end 5 dotimes",
+    },
+    While {
+        name: "while",
+        effect: "( body condition -- ... )",
+        description: "Calls 'body' as long as 'condition' returns true.",
+        example: "1 begin 2 * end begin dup 100 < end while",
+    },
+    // arithmetic
+    Plus {
+        name: "+",
+        effect: "( a b -- a+b )",
+        description: "Adds two numbers.",
+        example: "1 2 +",
+    },
+    Minus {
+        name: "-",
+        effect: "( a b -- a-b )",
+        description: "Subtracts two numbers.",
+        example: "5 3 -",
+    },
+    Div {
+        name: "/",
+        effect: "( a b -- a/b )",
+        description: "Divides two numbers.",
+        example: "10 2 /",
+    },
+    Mul {
+        name: "*",
+        effect: "( a b -- a*b )",
+        description: "Multiplies two numbers.",
+        example: "3 4 *",
+    },
+    Rem {
+        name: "%",
+        effect: "( a b -- a%b )",
+        description: "Remainder of the division of two numbers. Has the sign \
+                      of a.",
+        example: "7 3 %",
+    },
+    Mod {
+        name: "mod",
+        effect: "( a b -- a mod b )",
+        description: "Modulo of two numbers. Never negative.",
+        example: "-7 3 mod",
+    },
+    Pow {
+        name: "pow",
+        effect: "( a b -- a^b )",
+        description: "Raises a to the power b.",
+        example: "2 10 pow",
+    },
+    Min {
+        name: "min",
+        effect: "( a b -- min )",
+        description: "Pushes the smaller of two numbers.",
+        example: "3 5 min",
+    },
+    Max {
+        name: "max",
+        effect: "( a b -- max )",
+        description: "Pushes the larger of two numbers.",
+        example: "3 5 max",
+    },
+    Abs {
+        name: "abs",
+        effect: "( x -- |x| )",
+        description: "Pushes the absolute value of the number.",
+        example: "-4 abs",
+    },
+    Floor {
+        name: "floor",
+        effect: "( x -- x )",
+        description: "Rounds a float down.",
+        example: "2.7 floor",
+    },
+    Ceil {
+        name: "ceil",
+        effect: "( x -- x )",
+        description: "Rounds a float up.",
+        example: "2.2 ceil",
+    },
+    Round {
+        name: "round",
+        effect: "( x -- x )",
+        description: "Rounds a float to the nearest whole number, halfway \
+                      cases away from zero.",
+        example: "2.5 round",
+    },
+    Trunc {
+        name: "trunc",
+        effect: "( x -- x )",
+        description: "Removes the fractional part of a float.",
+        example: "-2.7 trunc",
+    },
+    Sqrt {
+        name: "sqrt",
+        effect: "( x -- float )",
+        description: "Pushes the square root of the number.",
+        example: "2 sqrt",
+    },
+    Sin {
+        name: "sin",
+        effect: "( x -- float )",
+        description: "Pushes the sine of x in radians.",
+        example: "pi 2.0 / sin",
+    },
+    Cos {
+        name: "cos",
+        effect: "( x -- float )",
+        description: "Pushes the cosine of x in radians.",
+        example: "pi cos",
+    },
+    Tan {
+        name: "tan",
+        effect: "( x -- float )",
+        description: "Pushes the tangent of x in radians.",
+        example: "pi 4.0 / tan",
+    },
+    Atan2 {
+        name: "atan2",
+        effect: "( y x -- float )",
+        description: "Pushes the angle of the point (x, y) in radians.",
+        example: "1 1 atan2",
+    },
+    Exp {
+        name: "exp",
+        effect: "( x -- float )",
+        description: "Pushes e to the power x.",
+        example: "1 exp",
+    },
+    Ln {
+        name: "ln",
+        effect: "( x -- float )",
+        description: "Pushes the natural logarithm of x.",
+        example: "e ln",
+    },
+    Log10 {
+        name: "log10",
+        effect: "( x -- float )",
+        description: "Pushes the base 10 logarithm of x.",
+        example: "1000 log10",
+    },
+    Pi {
+        name: "pi",
+        effect: "( -- float )",
+        description: "Pushes the number pi.",
+        example: "pi 2.0 *",
+    },
+    E {
+        name: "e",
+        effect: "( -- float )",
+        description: "Pushes Euler's number e.",
+        example: "e 2.0 pow",
+    },
+    // predicates
+    Greater {
+        name: ">",
+        effect: "( a b -- bool )",
+        description: "Checks if a is greater than b.",
+        example: "3 2 >",
+    },
+    GreaterEqual {
+        name: ">=",
+        effect: "( a b -- bool )",
+        description: "Checks if a is greater than or equal to b.",
+        example: "3 3 >=",
+    },
+    Equal {
+        name: "=",
+        effect: "( a b -- bool )",
+        description: "Checks if a is equal to b.",
+        example: "\"a\" \"a\" =",
+    },
+    LessEqual {
+        name: "<=",
+        effect: "( a b -- bool )",
+        description: "Checks if a is less than or equal to b.",
+        example: "2 3 <=",
+    },
+    Less {
+        name: "<",
+        effect: "( a b -- bool )",
+        description: "Checks if a is less than b.",
+        example: "2 3 <",
+    },
+    And {
+        name: "and",
+        effect: "( a b -- bool )",
+        description: "Logical conjunction of two booleans.",
+        example: "true false and",
+    },
+    Or {
+        name: "or",
+        effect: "( a b -- bool )",
+        description: "Logical disjunction of two booleans.",
+        example: "true false or",
+    },
+    Not {
+        name: "not",
+        effect: "( a -- bool )",
+        description: "Logical negation of a boolean.",
+        example: "false not",
+    },
+    // builtins
+    Print {
+        name: "print",
+        effect: "( value -- )",
+        description: "Prints the top of the stack to stdout.",
+        example: "\"hello\" print",
+    },
+    Println {
+        name: "println",
+        effect: "( value -- )",
+        description: "Prints the top of the stack and a newline to stdout.",
+        example: "\"hello\" println",
+    },
+    Eprint {
+        name: "eprint",
+        effect: "( value -- )",
+        description: "Prints the top of the stack to stderr.",
+        example: "\"warning\\n\" eprint",
+    },
+    Flush {
+        name: "flush",
+        effect: "( -- )",
+        description: "Writes everything printed so far to stdout and stderr.",
+        example: "\"name: \" print flush",
+    },
+    Showstack {
+        name: "showstack",
+        effect: "( -- )",
+        description: "Prints every element of the stack.",
+        example: "1 2 3 showstack",
+    },
+    Assert {
+        name: "assert",
+        effect: "( bool -- )",
+        description: "Fails if the top of the stack is false.",
+        example: "1 1 = assert",
+    },
+    Dup {
+        name: "dup",
+        effect: "( a -- a a )",
+        description: "Duplicates the top of the stack.",
+        example: "2 dup *",
+    },
+    Drop {
+        name: "drop",
+        effect: "( a -- )",
+        description: "Removes the top of the stack.",
+        example: "1 2 drop",
+    },
+    Swap {
+        name: "swap",
+        effect: "( a b -- b a )",
+        description: "Swaps the two topmost elements.",
+        example: "1 2 swap -",
+    },
+    Pick {
+        name: "pick",
+        effect: "( ... n -- ... value )",
+        description: "Copies the n-th element from the top to the top.",
+        example: "1 2 3 2 pick",
+    },
+    Clear {
+        name: "clear",
+        effect: "( ... -- )",
+        description: "Removes every element of the stack.",
+        example: "1 2 3 clear",
+    },
+    StackSize {
+        name: "stacksize",
+        effect: "( -- n )",
+        description: "Pushes the number of elements on the stack.",
+        example: "1 2 3 stacksize",
+    },
+    ReadLines {
+        name: "readlines",
+        effect: "( callback -- )",
+        description: "Calls 'callback' with every line of stdin until it \
+                      returns false.",
+        example: "begin print true end readlines",
+    },
+    ReadLine {
+        name: "readline",
+        effect: "( -- string bool )",
+        description: "Reads a line from stdin and pushes true, or an empty \
+                      string and false at the end of the input.",
+        example: "\"name: \" print readline drop",
+    },
+    ReadAll {
+        name: "readall",
+        effect: "( -- string )",
+        description: "Reads everything that is left on stdin.",
+        example: "readall length",
+    },
+    Load {
+        name: "load",
+        effect: "( path -- ... )",
+        description: "Runs the module at 'path' like a 'use' at runtime.",
+        example: "\"strategies/\" \"fast\" concat load",
+    },
+    Exit {
+        name: "exit",
+        effect: "( n -- )",
+        description: "Flushes the output and exits with status n.",
+        example: "1 exit",
+    },
+    Argc {
+        name: "argc",
+        effect: "( -- n )",
+        description: "Pushes the number of arguments after '--'.",
+        example: "argc",
+    },
+    Argv {
+        name: "argv",
+        effect: "( n -- string )",
+        description: "Pushes the n-th argument after '--', starting at 0.",
+        example: "0 argv",
+    },
+    Getenv {
+        name: "getenv",
+        effect: "( name -- value bool )",
+        description: "Pushes the environment variable and true, or an empty \
+                      string and false if it isn't set.",
+        example: "\"HOME\" getenv",
+    },
+    // files
+    ReadFile {
+        name: "readfile",
+        effect: "( path -- string )",
+        description: "Reads the whole file into a string.",
+        example: "\"notes.txt\" readfile",
+    },
+    WriteFile {
+        name: "writefile",
+        effect: "( string path -- )",
+        description: "Replaces the content of the file with the string.",
+        example: "\"hello\\n\" \"notes.txt\" writefile",
+    },
+    AppendFile {
+        name: "appendfile",
+        effect: "( string path -- )",
+        description: "Appends the string to the file.",
+        example: "\"more\\n\" \"notes.txt\" appendfile",
+    },
+    ReadFileLines {
+        name: "readfilelines",
+        effect: "( callback path -- )",
+        description: "Calls 'callback' with every line of the file until it \
+                      returns false.",
+        example: "begin print true end \"notes.txt\" readfilelines",
+    },
+    // casts
+    Natural {
+        name: "natural",
+        effect: "( number -- natural )",
+        description: "Converts a number or a string to a natural.",
+        example: "\"3.7\" natural",
+    },
+    Integer {
+        name: "integer",
+        effect: "( number -- integer )",
+        description: "Converts a number or a string to an integer.",
+        example: "3 integer",
+    },
+    BigInt {
+        name: "bigint",
+        effect: "( number -- bigint )",
+        description: "Converts a number or a string to an arbitrary-precision \
+                      integer.",
+        example: "2 bigint 100 pow",
+    },
+    Rational {
+        name: "rational",
+        effect: "( number -- rational )",
+        description: "Converts a number or a string to an exact fraction.",
+        example: "1 rational 3 /",
+    },
+    Float {
+        name: "float",
+        effect: "( number -- float )",
+        description: "Converts a number or a string to a float.",
+        example: "\"1e3\" float",
+    },
+    Boolean {
+        name: "boolean",
+        effect: "( string -- bool )",
+        description: "Converts \"true\" or \"false\" to a boolean.",
+        example: "\"true\" boolean",
+    },
+    ToString {
+        name: "tostring",
+        effect: "( value -- string )",
+        description: "Converts a value to the string 'print' would show.",
+        example: "42 tostring length",
+    },
+    // string operators
+    Concat {
+        name: "concat",
+        effect: "( a b -- ab )",
+        description: "Concatenates two strings.",
+        example: "\"foo\" \"bar\" concat",
+    },
+    Length {
+        name: "length",
+        effect: "( string -- string n )",
+        description: "Pushes the number of chars in the string.",
+        example: "\"hello\" length",
+    },
+    Contains {
+        name: "contains",
+        effect: "( string pattern -- bool )",
+        description: "Checks if the string contains the pattern.",
+        example: "\"hello\" \"ell\" contains",
+    },
+    Upcase {
+        name: "upcase",
+        effect: "( string -- STRING )",
+        description: "Converts the string to uppercase.",
+        example: "\"hello\" upcase",
+    },
+    Downcase {
+        name: "downcase",
+        effect: "( STRING -- string )",
+        description: "Converts the string to lowercase.",
+        example: "\"HELLO\" downcase",
+    },
+    Trim {
+        name: "trim",
+        effect: "( string -- string )",
+        description: "Removes leading and trailing whitespace.",
+        example: "\"  hello \" trim",
+    },
+    Format {
+        name: "format",
+        effect: "( ... format -- string )",
+        description: "Replaces every '{}' in the format with a value from the \
+                      stack. Supports Rust-like specs such as '{0}', '{:>8}', \
+                      '{:08.3}' and '{:x}', and '{{' and '}}' for braces.",
+        example: "\"pi\" 3.14159 \"{:<4}|{:>8.3}\" format",
+    },
+    Index {
+        name: "index",
+        effect: "( string n -- string char )",
+        description: "Pushes the n-th char of the string.",
+        example: "\"hello\" 1 index",
+    },
+    Split {
+        name: "split",
+        effect: "( string separator -- s1 ... sn n )",
+        description: "Splits the string at every separator and pushes the \
+                      pieces and their number. An empty separator splits \
+                      the string into chars.",
+        example: "\"a,b,c\" \",\" split",
+    },
+    Join {
+        name: "join",
+        effect: "( s1 ... sn n separator -- string )",
+        description: "Joins n strings with the separator between them.",
+        example: "\"a\" \"b\" \"c\" 3 \", \" join",
+    },
+    Replace {
+        name: "replace",
+        effect: "( string from to -- string )",
+        description: "Replaces every occurrence of 'from' with 'to'.",
+        example: "\"a-b-c\" \"-\" \"+\" replace",
+    },
+    Substring {
+        name: "substring",
+        effect: "( string start end -- string )",
+        description: "Pushes the chars from 'start' up to but excluding \
+                      'end'.",
+        example: "\"hello\" 1 4 substring",
+    },
+    StartsWith {
+        name: "startswith",
+        effect: "( string prefix -- bool )",
+        description: "Checks if the string starts with the prefix.",
+        example: "\"hello\" \"he\" startswith",
+    },
+    EndsWith {
+        name: "endswith",
+        effect: "( string suffix -- bool )",
+        description: "Checks if the string ends with the suffix.",
+        example: "\"hello\" \"lo\" endswith",
+    },
+    Find {
+        name: "find",
+        effect: "( string pattern -- n bool )",
+        description: "Pushes the char position of the first occurrence of \
+                      the pattern and true, or 0 and false if there is none.",
+        example: "\"hello\" \"l\" find",
+    },
+    Repeat {
+        name: "repeat",
+        effect: "( string n -- string )",
+        description: "Repeats the string n times.",
+        example: "\"ab\" 3 repeat",
+    },
+    Reverse {
+        name: "reverse",
+        effect: "( string -- string )",
+        description: "Reverses the chars of the string.",
+        example: "\"hello\" reverse",
+    },
+    Ord {
+        name: "ord",
+        effect: "( char -- n )",
+        description: "Pushes the code point of a single char.",
+        example: "\"A\" ord",
+    },
+    Chr {
+        name: "chr",
+        effect: "( n -- char )",
+        description: "Pushes the char with the code point n.",
+        example: "65 chr",
+    },
+    // regular expressions
+    ReMatch {
+        name: "rematch",
+        effect: "( string regex -- bool )",
+        description: "Checks if the regex matches somewhere in the string.",
+        example: "\"id=42\" \"[0-9]+\" rematch",
+    },
+    ReFind {
+        name: "refind",
+        effect: "( string regex -- match bool )",
+        description: "Pushes the first match of the regex and true, or an \
+                      empty string and false if there is none.",
+        example: "\"id=42\" \"[0-9]+\" refind",
+    },
+    ReCaptures {
+        name: "recaptures",
+        effect: "( string regex -- match g1 ... gn n )",
+        description: "Pushes the first match, its capture groups and their \
+                      number including the match. Pushes only 0 if there is \
+                      no match.",
+        example: "\"id=42\" \"(\\\\w+)=(\\\\d+)\" recaptures",
+    },
+    ReReplace {
+        name: "rereplace",
+        effect: "( string regex replacement -- string )",
+        description: "Replaces every match of the regex. '$1' or '${name}' in \
+                      the replacement refer to capture groups.",
+        example: "\"a=1 b=2\" \"(\\\\w)=(\\\\d)\" \"$2=$1\" rereplace",
+    },
+}
+
+impl Operator {
+    pub fn info(self) -> &'static OperatorInfo {
+        &OPERATORS[self as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<Operator> {
+        OPERATORS
+            .iter()
+            .find(|info| info.name == name)
+            .map(|info| info.operator)
+    }
+
+    // NOTE: Only the operators of the original language are keywords. Named
+    // operators added since then are lexed as identifiers and only run when
+    // nothing is bound to the name, so programs that use these names for their
+    // own definitions keep working.
+    pub fn is_keyword(self) -> bool {
+        matches!(
+            self,
+            Operator::Plus
+                | Operator::Minus
+                | Operator::Mul
+                | Operator::Div
+                | Operator::Rem
+                | Operator::Less
+                | Operator::LessEqual
+                | Operator::Equal
+                | Operator::Greater
+                | Operator::GreaterEqual
+                | Operator::If
+                | Operator::Dotimes
+                | Operator::While
+                | Operator::And
+                | Operator::Or
+                | Operator::Not
+                | Operator::Print
+                | Operator::Showstack
+                | Operator::ReadLines
+                | Operator::Assert
+                | Operator::Dup
+                | Operator::Drop
+                | Operator::Swap
+                | Operator::Pick
+                | Operator::Clear
+                | Operator::StackSize
+                | Operator::Natural
+                | Operator::Integer
+                | Operator::Float
+                | Operator::Concat
+                | Operator::Length
+                | Operator::Contains
+                | Operator::Downcase
+                | Operator::Upcase
+                | Operator::Trim
+                | Operator::Format
+                | Operator::Index
+        )
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.info().name)
+    }
+}
+
+impl fmt::Display for OperatorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}\n    {}\n    example: {}",
+            self.name, self.stack_effect, self.description, self.example
+        )
+    }
+}
@@ -0,0 +1,266 @@
+use super::BigInt;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// NOTE: Rationals are always reduced and the denominator is positive, so
+// equal values have equal representations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rational {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Rational {
+    pub fn new(numer: BigInt, denom: BigInt) -> Option<Rational> {
+        if denom.is_zero() {
+            return None;
+        }
+
+        let rational = if denom.is_negative() {
+            Rational {
+                numer: -&numer,
+                denom: -&denom,
+            }
+        } else {
+            Rational { numer, denom }
+        };
+
+        Some(rational.reduced())
+    }
+
+    // NOTE: Only used with a positive denominator, so the division by the
+    // greatest common divisor can't fail.
+    fn reduced(self) -> Rational {
+        let gcd = self.numer.gcd(&self.denom);
+
+        Rational {
+            numer: self.numer.checked_div(&gcd).unwrap_or_default(),
+            denom: self.denom.checked_div(&gcd).unwrap_or_default(),
+        }
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+
+    pub fn is_whole(&self) -> bool {
+        self.denom == BigInt::one()
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational {
+            numer: self.numer.abs(),
+            denom: self.denom.clone(),
+        }
+    }
+
+    pub fn recip(&self) -> Option<Rational> {
+        Rational::new(self.denom.clone(), self.numer.clone())
+    }
+
+    // NOTE: Every finite float is a fraction with a power of two as its
+    // denominator, so the conversion is exact.
+    pub fn from_f64(f: f64) -> Option<Rational> {
+        if !f.is_finite() {
+            return None;
+        }
+
+        let bits = f.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, biased_exponent - 1075)
+        };
+
+        let mut mantissa = BigInt::from(mantissa);
+        if f.is_sign_negative() {
+            mantissa = -&mantissa;
+        }
+        let scale = BigInt::from(2u64).pow(exponent.unsigned_abs());
+
+        if exponent >= 0 {
+            Some(Rational::from(&mantissa * &scale))
+        } else {
+            Rational::new(mantissa, scale)
+        }
+    }
+
+    // NOTE: Both parts are cut down to 64 bits first, so huge numerators or
+    // denominators don't turn into infinities.
+    pub fn to_f64(&self) -> f64 {
+        let scale_down = |n: &BigInt| {
+            let shift = n.bits().saturating_sub(64);
+            let divisor = BigInt::from(2u64).pow(shift);
+            let scaled = n.checked_div(&divisor).unwrap_or_default();
+            (scaled.to_f64(), shift as i32)
+        };
+        let (numer, numer_shift) = scale_down(&self.numer);
+        let (denom, denom_shift) = scale_down(&self.denom);
+        let exponent = numer_shift - denom_shift;
+
+        numer / denom
+            * 2f64.powi(exponent / 2)
+            * 2f64.powi(exponent - exponent / 2)
+    }
+
+    pub fn checked_add(&self, other: &Rational) -> Option<Rational> {
+        Some(self + other)
+    }
+
+    pub fn checked_sub(&self, other: &Rational) -> Option<Rational> {
+        Some(self - other)
+    }
+
+    pub fn checked_mul(&self, other: &Rational) -> Option<Rational> {
+        Some(self * other)
+    }
+
+    pub fn checked_div(&self, other: &Rational) -> Option<Rational> {
+        Some(self * &other.recip()?)
+    }
+
+    pub fn checked_rem(&self, other: &Rational) -> Option<Rational> {
+        let quotient = self.checked_div(other)?.trunc();
+        Some(self - &(other * &quotient))
+    }
+
+    // NOTE: The quotient is rounded so that the remainder is never negative.
+    pub fn checked_rem_euclid(&self, other: &Rational) -> Option<Rational> {
+        let quotient = self.checked_div(other)?;
+        let quotient = if other.is_negative() {
+            quotient.ceil()
+        } else {
+            quotient.floor()
+        };
+
+        Some(self - &(other * &quotient))
+    }
+
+    pub fn pow(&self, exponent: u32) -> Rational {
+        Rational {
+            numer: self.numer.pow(exponent),
+            denom: self.denom.pow(exponent),
+        }
+    }
+
+    pub fn trunc(&self) -> Rational {
+        Rational::from(self.numer.div_rem(&self.denom).unwrap_or_default().0)
+    }
+
+    pub fn floor(&self) -> Rational {
+        Rational::from(
+            self.numer.div_rem_euclid(&self.denom).unwrap_or_default().0,
+        )
+    }
+
+    pub fn ceil(&self) -> Rational {
+        -&(-self).floor()
+    }
+
+    // NOTE: Halfway cases are rounded away from zero like f64::round.
+    pub fn round(&self) -> Rational {
+        let half = Rational {
+            numer: BigInt::one(),
+            denom: BigInt::from(2u64),
+        };
+        let rounded = (&self.abs() + &half).floor();
+
+        if self.is_negative() {
+            -&rounded
+        } else {
+            rounded
+        }
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(numer: BigInt) -> Rational {
+        Rational {
+            numer,
+            denom: BigInt::one(),
+        }
+    }
+}
+
+// NOTE: Whole rationals are shown as whole numbers, so '4/2' and '1/2 1/2 +'
+// print '2' and '1'.
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_whole() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    fn add(self, other: &Rational) -> Rational {
+        Rational {
+            numer: &(&self.numer * &other.denom)
+                + &(&other.numer * &self.denom),
+            denom: &self.denom * &other.denom,
+        }
+        .reduced()
+    }
+}
+
+impl Sub for &Rational {
+    type Output = Rational;
+
+    fn sub(self, other: &Rational) -> Rational {
+        self + &-other
+    }
+}
+
+impl Mul for &Rational {
+    type Output = Rational;
+
+    fn mul(self, other: &Rational) -> Rational {
+        Rational {
+            numer: &self.numer * &other.numer,
+            denom: &self.denom * &other.denom,
+        }
+        .reduced()
+    }
+}
+
+#[cfg(test)]
+mod test;
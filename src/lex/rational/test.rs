@@ -0,0 +1,72 @@
+use proptest::prelude::*;
+
+use super::*;
+
+fn ratio(numer: i64, denom: i64) -> Rational {
+    Rational::new(BigInt::from(numer), BigInt::from(denom)).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn test_from_f64(f in any::<f64>()) {
+        match Rational::from_f64(f) {
+            Some(r) => prop_assert_eq!(r.to_f64(), f),
+            None => prop_assert!(!f.is_finite()),
+        }
+    }
+
+    #[test]
+    fn test_rounding(numer in -1000i64..1000, denom in 1i64..100) {
+        let r = ratio(numer, denom);
+        let f = numer as f64 / denom as f64;
+
+        prop_assert_eq!(r.trunc().to_f64(), f.trunc());
+        prop_assert_eq!(r.floor().to_f64(), f.floor());
+        prop_assert_eq!(r.ceil().to_f64(), f.ceil());
+        prop_assert_eq!(r.round().to_f64(), f.round());
+    }
+}
+
+#[test]
+fn test_new() {
+    assert_eq!(ratio(2, 4), ratio(1, 2));
+    assert_eq!(ratio(3, -6), ratio(-1, 2));
+    assert_eq!(ratio(0, -5), ratio(0, 1));
+    assert_eq!(ratio(-4, -2).to_string(), "2");
+    assert_eq!(ratio(0, 3).to_string(), "0");
+    assert_eq!(ratio(3, -6).to_string(), "-1/2");
+    assert_eq!(Rational::new(BigInt::one(), BigInt::zero()), None);
+}
+
+#[test]
+fn test_arithmetic() {
+    assert_eq!(&ratio(1, 2) + &ratio(1, 3), ratio(5, 6));
+    assert_eq!(&ratio(1, 2) - &ratio(3, 4), ratio(-1, 4));
+    assert_eq!(&ratio(2, 3) * &ratio(3, 4), ratio(1, 2));
+    assert_eq!(ratio(1, 2).checked_div(&ratio(-1, 4)), Some(ratio(-2, 1)));
+    assert_eq!(ratio(1, 2).checked_div(&ratio(0, 1)), None);
+    assert_eq!(ratio(-2, 3).pow(3), ratio(-8, 27));
+    assert_eq!(ratio(-2, 3).recip(), Some(ratio(-3, 2)));
+}
+
+#[test]
+fn test_remainder() {
+    assert_eq!(ratio(7, 2).checked_rem(&ratio(1, 1)), Some(ratio(1, 2)));
+    assert_eq!(ratio(-7, 2).checked_rem(&ratio(1, 1)), Some(ratio(-1, 2)));
+    assert_eq!(
+        ratio(-7, 2).checked_rem_euclid(&ratio(1, 1)),
+        Some(ratio(1, 2))
+    );
+    assert_eq!(
+        ratio(7, 2).checked_rem_euclid(&ratio(-2, 1)),
+        Some(ratio(3, 2))
+    );
+    assert_eq!(ratio(7, 2).checked_rem(&ratio(0, 1)), None);
+}
+
+#[test]
+fn test_compare() {
+    assert!(ratio(1, 3) < ratio(1, 2));
+    assert!(ratio(-1, 2) < ratio(-1, 3));
+    assert!(ratio(7, 7) == ratio(1, 1));
+}
@@ -9,7 +9,7 @@ fn compare_token_lists(
 
     assert_eq!(result.len(), expected.len());
 
-    for (actual, expected) in result.into_iter().zip(expected.into_iter()) {
+    for (actual, expected) in result.into_iter().zip(expected) {
         let (line, token, lexeme) = expected;
         let lexeme = lexeme.to_owned();
         assert_eq!(
@@ -223,50 +223,74 @@ fn test_numbers_float() {
 }
 
 #[test]
-fn test_numbers_overflow() {
+fn test_big_numbers() {
     let lexer = Lexer::new(
         "85892349393234324592 -858243349923432034592 +85648993234023044592",
         Rc::new(ProgramSource::Stdin),
     );
+    let big = |s: &str| Ok(Token::Number(Number::BigInt(s.parse().unwrap())));
     let expected = vec![
-        (
-            1,
-            Err(PileError::in_line(
-                Rc::new(ProgramSource::Stdin),
-                1,
-                "'85892349393234324592' is too large to be represented as a \
-                 number"
-                    .to_string(),
-            )),
-            "85892349393234324592",
-        ),
-        (
-            1,
-            Err(PileError::in_line(
-                Rc::new(ProgramSource::Stdin),
-                1,
-                "'-858243349923432034592' is too small to be represented as a \
-                 number"
-                    .to_string(),
-            )),
-            "-858243349923432034592",
-        ),
-        (
+        (1, big("85892349393234324592"), "85892349393234324592"),
+        (1, big("-858243349923432034592"), "-858243349923432034592"),
+        (1, big("85648993234023044592"), "+85648993234023044592"),
+    ];
+
+    compare_token_lists(lexer, expected);
+}
+
+#[test]
+fn test_rationals() {
+    let lexer = Lexer::new(
+        "1/3 -4/6 +2/1 1/0 1/-2 1/2/3",
+        Rc::new(ProgramSource::Stdin),
+    );
+    let ratio = |numer: i64, denom: i64| {
+        Ok(Token::Number(Number::Rational(
+            Rational::new(BigInt::from(numer), BigInt::from(denom)).unwrap(),
+        )))
+    };
+    let error = |msg: &str| {
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
             1,
-            Err(PileError::in_line(
-                Rc::new(ProgramSource::Stdin),
-                1,
-                "'+85648993234023044592' is too large to be represented as a \
-                 number"
-                    .to_string(),
-            )),
-            "+85648993234023044592",
-        ),
+            msg.to_owned(),
+        ))
+    };
+    let expected = vec![
+        (1, ratio(1, 3), "1/3"),
+        (1, ratio(-2, 3), "-4/6"),
+        (1, ratio(2, 1), "+2/1"),
+        (1, error("'1/0' has a zero denominator"), "1/0"),
+        (1, error("'1/-2' isn't a number"), "1/-2"),
+        (1, error("'1/2/3' isn't a number"), "1/2/3"),
     ];
 
     compare_token_lists(lexer, expected);
 }
 
+#[test]
+fn test_number_source_fmt() {
+    let big = |s: &str| Number::BigInt(s.parse().unwrap());
+
+    assert_eq!(big("-5").source_fmt(), "-5 bigint");
+    assert_eq!(
+        big("18446744073709551615").source_fmt(),
+        "18446744073709551615 bigint"
+    );
+    assert_eq!(
+        big("18446744073709551616").source_fmt(),
+        "18446744073709551616"
+    );
+    assert_eq!(
+        parse_number("-2/4").map(|n| n.source_fmt()),
+        Ok("-1/2".to_owned())
+    );
+    assert_eq!(
+        parse_number("4/2").map(|n| n.source_fmt()),
+        Ok("2/1".to_owned())
+    );
+}
+
 #[test]
 fn test_boolean() {
     let lexer = Lexer::new(
@@ -740,3 +764,111 @@ fn test_token_fmt() {
     assert_eq!(format!("{}", Token::Boolean(false)), "false");
     assert_eq!(format!("{}", Token::Identifier("var".to_owned())), "var");
 }
+
+#[test]
+fn test_operator_table() {
+    for (index, info) in OPERATORS.iter().enumerate() {
+        assert_eq!(info.operator as usize, index);
+        assert_eq!(info.operator.info(), info);
+        assert_eq!(info.operator.to_string(), info.name);
+
+        let mut lexer = Lexer::new(info.name, Rc::new(ProgramSource::Stdin));
+        let token = if info.operator.is_keyword() {
+            Token::Operator(info.operator)
+        } else {
+            Token::Identifier(info.name.to_owned())
+        };
+        assert_eq!(lexer.next().map(|item| item.token), Some(Ok(token)));
+
+        let example = Lexer::new(info.example, Rc::new(ProgramSource::Stdin));
+        assert!(example.into_iter().all(|item| item.token.is_ok()));
+    }
+}
+
+#[test]
+fn test_token_source_fmt() {
+    let sources = vec![
+        Token::Number(Number::Natural(12)),
+        Token::Number(Number::Integer(-3)),
+        Token::Number(Number::Float(2.0)),
+        Token::Number(Number::Float(-0.5)),
+        Token::Number(Number::Float(1e100)),
+        Token::String("tab\t \"quoted\" \\ \n\r\0".to_owned()),
+        Token::Operator(Operator::Dup),
+        Token::Boolean(true),
+    ];
+
+    for token in sources {
+        let source = token.source_fmt();
+        let mut lexer = Lexer::new(&source, Rc::new(ProgramSource::Stdin));
+
+        assert_eq!(lexer.next().map(|item| item.token), Some(Ok(token)));
+        assert_eq!(lexer.next(), None);
+    }
+
+    assert_eq!(Number::Integer(3).source_fmt(), "3 integer");
+    assert_eq!(Number::Float(f64::NAN).source_fmt(), "0.0 0.0 /");
+    assert_eq!(Number::Float(f64::NEG_INFINITY).source_fmt(), "-1.0 0.0 /");
+}
+
+#[test]
+fn test_qualified_names() {
+    let lexer = Lexer::new(
+        "use \"math\" as m m.sqrt M.Inner.Value a.b2 a. .b a..b m.2",
+        Rc::new(ProgramSource::Stdin),
+    );
+
+    compare_token_lists(
+        lexer,
+        vec![
+            (1, Ok(Token::Use), "use"),
+            (1, Ok(Token::String("math".to_owned())), "\"math\""),
+            (1, Ok(Token::As), "as"),
+            (1, Ok(Token::Identifier("m".to_owned())), "m"),
+            (1, Ok(Token::Identifier("m.sqrt".to_owned())), "m.sqrt"),
+            (
+                1,
+                Ok(Token::Identifier("m.inner.value".to_owned())),
+                "M.Inner.Value",
+            ),
+            (1, Ok(Token::Identifier("a.b2".to_owned())), "a.b2"),
+            (
+                1,
+                Err(PileError::in_line(
+                    Rc::new(ProgramSource::Stdin),
+                    1,
+                    "Invalid qualified name 'a.'".to_owned(),
+                )),
+                "a.",
+            ),
+            (
+                1,
+                Err(PileError::in_line(
+                    Rc::new(ProgramSource::Stdin),
+                    1,
+                    "Unknown char '.'".to_owned(),
+                )),
+                ".",
+            ),
+            (1, Ok(Token::Identifier("b".to_owned())), "b"),
+            (
+                1,
+                Err(PileError::in_line(
+                    Rc::new(ProgramSource::Stdin),
+                    1,
+                    "Invalid qualified name 'a..b'".to_owned(),
+                )),
+                "a..b",
+            ),
+            (
+                1,
+                Err(PileError::in_line(
+                    Rc::new(ProgramSource::Stdin),
+                    1,
+                    "Invalid qualified name 'm.2'".to_owned(),
+                )),
+                "m.2",
+            ),
+        ],
+    );
+}
@@ -4,12 +4,20 @@ mod formatting;
 mod interpret;
 mod lex;
 mod locals;
+mod manifest;
 mod parse;
 mod pile_error;
 mod program_source;
 mod repl;
+mod stdlib;
 mod using;
 
+use program_source::ProgramSource;
+
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
 fn main() {
     if let Err(msg) = pile() {
         eprintln!("{}", msg);
@@ -19,6 +27,18 @@ fn main() {
 
 fn pile() -> Result<(), String> {
     let options = cli::read_options(std::env::args_os())?;
+
+    if let Some(name) = options.operator_help() {
+        let info = completion::operator_info(name)
+            .ok_or_else(|| format!("Unknown operator '{}'", name))?;
+        println!("{}", info);
+        return Ok(());
+    }
+
+    if let Some(files) = options.test_files() {
+        return run_tests(files, &options);
+    }
+
     let program_text = options.read_program()?;
 
     let lexer = lex::Lexer::new(program_text.as_ref(), options.source());
@@ -32,7 +52,8 @@ fn pile() -> Result<(), String> {
     let ast = parser.parse().map_err(|e| e.to_string())?;
 
     let ast = locals::translate(ast);
-    let ast = using::resolve(ast).map_err(|e| e.to_string())?;
+    let ast = using::resolve(ast, options.search_path())
+        .map_err(|e| e.to_string())?;
 
     match options.completion() {
         None => {
@@ -41,15 +62,89 @@ fn pile() -> Result<(), String> {
                 options.stack_size(),
                 options.trace(),
             );
-            interpreter.run().map_err(|e| e.to_string())?;
-        }
-        Some(cli::CompletionOptions { prefix, line }) => {
-            completion::complete_to_stdout(prefix, *line, &ast)
+            interpreter.set_search_path(options.search_path());
+            interpreter.set_args(options.args());
+            interpreter.set_input_mode(options.lossy(), options.keep_cr());
+            interpreter.set_strict(options.strict());
+            let status = match interpreter.run() {
+                Ok(top) if options.status_from_stack() => {
+                    Some(interpret::exit_status(top)?)
+                }
+                Ok(_) => None,
+                Err(err) => {
+                    Some(err.exit_status().ok_or_else(|| err.to_string())?)
+                }
+            };
+            interpreter.flush()?;
+
+            if let Some(status) = status {
+                std::process::exit(status);
+            }
         }
+        Some(cli::CompletionOptions {
+            prefix,
+            line,
+            verbose,
+        }) => completion::complete_to_stdout(prefix, *line, *verbose, &ast),
     }
 
     Ok(())
 }
 
+fn run_tests(
+    files: &[PathBuf],
+    options: &cli::CommandLineOptions,
+) -> Result<(), String> {
+    let mut failed = 0;
+
+    for file in files {
+        match run_test(file, options) {
+            Ok(()) => println!("ok      {}", file.to_string_lossy()),
+            Err(msg) => {
+                failed += 1;
+                println!("FAILED  {}\n    {}", file.to_string_lossy(), msg);
+            }
+        }
+    }
+
+    println!("\n{} passed, {} failed", files.len() - failed, failed);
+
+    if failed > 0 {
+        Err(format!("{} test(s) failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn run_test(
+    file: &PathBuf,
+    options: &cli::CommandLineOptions,
+) -> Result<(), String> {
+    let program_text = fs::read_to_string(file)
+        .map_err(|err| format!("{}: {}", file.to_string_lossy(), err))?;
+    let source = Rc::new(ProgramSource::File(file.clone()));
+
+    let lexer = lex::Lexer::new(&program_text, source);
+    let ast = parse::Parser::new(lexer)
+        .parse()
+        .map_err(|e| e.to_string())?;
+    let ast = using::resolve(locals::translate(ast), options.search_path())
+        .map_err(|e| e.to_string())?;
+
+    let mut interpreter =
+        interpret::Interpreter::new(ast, options.stack_size(), options.trace());
+    interpreter.set_search_path(options.search_path());
+    interpreter.set_args(options.args());
+    interpreter.set_input_mode(options.lossy(), options.keep_cr());
+    interpreter.set_strict(options.strict());
+    // NOTE: A test that exits with status 0 passes.
+    match interpreter.run() {
+        Err(err) if err.exit_status() != Some(0) => return Err(err.to_string()),
+        _ => (),
+    }
+
+    interpreter.flush()
+}
+
 #[cfg(test)]
 mod examples;
@@ -0,0 +1,334 @@
+use std::collections::HashSet;
+use std::fs;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+pub const MANIFEST_FILE: &str = "pile.toml";
+
+// NOTE: Every path in the manifest is relative to the directory of the
+// manifest and stored as such after parsing.
+#[derive(Debug, PartialEq)]
+pub struct Manifest {
+    pub root: PathBuf,
+    pub name: Option<String>,
+    pub entry: PathBuf,
+    pub sources: Vec<PathBuf>,
+    pub tests: Vec<PathBuf>,
+    pub dependencies: Vec<(String, PathBuf)>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    String(String),
+    Array(Vec<String>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Section {
+    None,
+    Package,
+    Dependencies,
+}
+
+impl Manifest {
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(MANIFEST_FILE))
+            .find(|file| file.is_file())
+    }
+
+    pub fn read(file: &Path) -> Result<Manifest, String> {
+        let text = fs::read_to_string(file)
+            .map_err(|err| format!("{}: {}", file.to_string_lossy(), err))?;
+
+        Manifest::parse(&text, file)
+    }
+
+    pub fn parse(text: &str, file: &Path) -> Result<Manifest, String> {
+        let root = file
+            .parent()
+            .map(Path::to_owned)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let mut manifest = Manifest {
+            root: root.clone(),
+            name: None,
+            entry: PathBuf::new(),
+            sources: vec![],
+            tests: vec![],
+            dependencies: vec![],
+        };
+        let mut section = Section::None;
+        let mut seen = HashSet::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let error = |msg: String| {
+                format!("{}:{}: {}", file.to_string_lossy(), index + 1, msg)
+            };
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = strip_comment(name)
+                    .strip_suffix(']')
+                    .ok_or_else(|| {
+                        error("Expected ']' after section name.".to_owned())
+                    })?
+                    .trim();
+                section = match name {
+                    "package" => Section::Package,
+                    "dependencies" => Section::Dependencies,
+                    name => {
+                        return Err(error(format!(
+                            "Unknown section '{}'.",
+                            name
+                        )))
+                    }
+                };
+                continue;
+            }
+
+            let (key, value) = key_value(line).map_err(error)?;
+            if !seen.insert((section, key.clone())) {
+                return Err(error(format!("Duplicate key '{}'.", key)));
+            }
+
+            match (section, key.as_str(), value) {
+                (Section::None, _, _) => {
+                    return Err(error(format!(
+                        "Key '{}' outside of a section.",
+                        key
+                    )))
+                }
+                (Section::Package, "name", Value::String(name)) => {
+                    manifest.name = Some(name)
+                }
+                (Section::Package, "entry", Value::String(entry)) => {
+                    manifest.entry = root.join(entry)
+                }
+                (Section::Package, "sources", Value::Array(sources)) => {
+                    manifest.sources =
+                        sources.iter().map(|dir| root.join(dir)).collect()
+                }
+                (Section::Package, "tests", Value::Array(tests)) => {
+                    manifest.tests =
+                        tests.iter().map(|dir| root.join(dir)).collect()
+                }
+                (Section::Package, "name", _)
+                | (Section::Package, "entry", _) => {
+                    return Err(error(format!(
+                        "Expected a string for '{}'.",
+                        key
+                    )))
+                }
+                (Section::Package, "sources", _)
+                | (Section::Package, "tests", _) => {
+                    return Err(error(format!(
+                        "Expected an array of strings for '{}'.",
+                        key
+                    )))
+                }
+                (Section::Package, key, _) => {
+                    return Err(error(format!(
+                        "Unknown key '{}' in [package].",
+                        key
+                    )))
+                }
+                (Section::Dependencies, _, Value::String(path)) => {
+                    manifest.dependencies.push((key, root.join(path)))
+                }
+                (Section::Dependencies, _, Value::Array(_)) => {
+                    return Err(error(format!(
+                        "Expected the path of dependency '{}'.",
+                        key
+                    )))
+                }
+            }
+        }
+
+        if manifest.entry.as_os_str().is_empty() {
+            return Err(format!(
+                "{}: Missing 'entry' in [package].",
+                file.to_string_lossy()
+            ));
+        }
+        if manifest.sources.is_empty() {
+            manifest.sources.push(root);
+        }
+
+        Ok(manifest)
+    }
+
+    // NOTE: The source roots of the project come first, then the ones of
+    // every dependency. A dependency without a manifest is a source root.
+    pub fn search_path(&self) -> Result<Vec<PathBuf>, String> {
+        let mut search_path = vec![];
+        let mut visited = HashSet::new();
+        self.add_search_path(&mut search_path, &mut visited)?;
+
+        Ok(search_path)
+    }
+
+    fn add_search_path(
+        &self,
+        search_path: &mut Vec<PathBuf>,
+        visited: &mut HashSet<PathBuf>,
+    ) -> Result<(), String> {
+        if let Ok(root) = self.root.canonicalize() {
+            if !visited.insert(root) {
+                return Ok(());
+            }
+        }
+
+        search_path.extend(self.sources.iter().cloned());
+
+        for (name, path) in &self.dependencies {
+            if !path.is_dir() {
+                return Err(format!(
+                    "Dependency '{}' isn't a directory: {}",
+                    name,
+                    path.to_string_lossy()
+                ));
+            }
+
+            let manifest = path.join(MANIFEST_FILE);
+            if manifest.is_file() {
+                Manifest::read(&manifest)?
+                    .add_search_path(search_path, visited)?;
+            } else {
+                search_path.push(path.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn test_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = vec![];
+
+        for dir in &self.tests {
+            let entries = fs::read_dir(dir)
+                .map_err(|err| format!("{}: {}", dir.to_string_lossy(), err))?;
+
+            for entry in entries {
+                let path = entry
+                    .map_err(|err| {
+                        format!("{}: {}", dir.to_string_lossy(), err)
+                    })?
+                    .path();
+                if path.extension().unwrap_or_default() == "pile" {
+                    files.push(path);
+                }
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+}
+
+fn strip_comment(text: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return text[..index].trim_end(),
+            _ => (),
+        }
+    }
+
+    text.trim_end()
+}
+
+fn key_value(line: &str) -> Result<(String, Value), String> {
+    let (key, value) = line
+        .split_once('=')
+        .ok_or_else(|| "Expected 'key = value'.".to_owned())?;
+    let key = key.trim();
+
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("Invalid key '{}'.", key));
+    }
+
+    let mut chars = strip_comment(value).trim().chars().peekable();
+    let value = match chars.peek() {
+        Some('"') => Value::String(string(&mut chars)?),
+        Some('[') => Value::Array(array(&mut chars)?),
+        _ => {
+            return Err(format!("Expected a string or an array for '{}'.", key))
+        }
+    };
+
+    if chars.next().is_some() {
+        return Err(format!(
+            "Unexpected characters after the value of '{}'.",
+            key
+        ));
+    }
+
+    Ok((key.to_owned(), value))
+}
+
+fn string(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    chars.next();
+    let mut string = String::new();
+
+    loop {
+        match chars.next() {
+            None => return Err("Unterminated string.".to_owned()),
+            Some('"') => return Ok(string),
+            Some('\\') => match chars.next() {
+                Some('"') => string.push('"'),
+                Some('\\') => string.push('\\'),
+                Some('n') => string.push('\n'),
+                Some('t') => string.push('\t'),
+                Some(c) => return Err(format!("Unknown escape '\\{}'.", c)),
+                None => return Err("Unterminated string.".to_owned()),
+            },
+            Some(c) => string.push(c),
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn array(chars: &mut Peekable<Chars>) -> Result<Vec<String>, String> {
+    chars.next();
+    let mut strings = vec![];
+
+    loop {
+        skip_whitespace(chars);
+        match chars.peek() {
+            Some(']') => {
+                chars.next();
+                return Ok(strings);
+            }
+            Some('"') => strings.push(string(chars)?),
+            _ => return Err("Expected a string or ']' in array.".to_owned()),
+        }
+
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => (),
+            Some(']') => return Ok(strings),
+            _ => return Err("Expected ',' or ']' in array.".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,155 @@
+use super::*;
+
+fn parse(text: &str) -> Result<Manifest, String> {
+    Manifest::parse(text, Path::new("/project/pile.toml"))
+}
+
+#[test]
+fn test_parse_manifest() {
+    let manifest = parse(
+        r#"
+# comment
+[package]
+name = "demo" # trailing comment
+entry = "src/main.pile"
+sources = ["src", "lib",]
+tests = [ "tests" ]
+
+[dependencies]
+utils = "../utils"
+"#,
+    );
+
+    assert_eq!(
+        manifest,
+        Ok(Manifest {
+            root: PathBuf::from("/project"),
+            name: Some("demo".to_owned()),
+            entry: PathBuf::from("/project/src/main.pile"),
+            sources: vec![
+                PathBuf::from("/project/src"),
+                PathBuf::from("/project/lib")
+            ],
+            tests: vec![PathBuf::from("/project/tests")],
+            dependencies: vec![(
+                "utils".to_owned(),
+                PathBuf::from("/project/../utils")
+            )],
+        })
+    );
+}
+
+#[test]
+fn test_parse_defaults() {
+    let manifest = parse("[package]\nentry = \"main.pile\"").unwrap();
+
+    assert_eq!(manifest.name, None);
+    assert_eq!(manifest.sources, vec![PathBuf::from("/project")]);
+    assert!(manifest.tests.is_empty());
+    assert!(manifest.dependencies.is_empty());
+}
+
+#[test]
+fn test_parse_strings() {
+    let manifest =
+        parse("[package]\nentry = \"a \\\"b\\\" # c\\\\d.pile\"").unwrap();
+
+    assert_eq!(
+        manifest.entry,
+        PathBuf::from("/project/a \"b\" # c\\d.pile")
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let error =
+        |line, msg: &str| Err(format!("/project/pile.toml:{}: {}", line, msg));
+
+    assert_eq!(
+        parse("name = \"x\""),
+        error(1, "Key 'name' outside of a section.")
+    );
+    assert_eq!(
+        parse("[package"),
+        error(1, "Expected ']' after section name.")
+    );
+    assert_eq!(
+        parse("\n[workspace]"),
+        error(2, "Unknown section 'workspace'.")
+    );
+    assert_eq!(
+        parse("[package]\nversion = \"1.0\""),
+        error(2, "Unknown key 'version' in [package].")
+    );
+    assert_eq!(
+        parse("[package]\nentry = \"a\"\nentry = \"b\""),
+        error(3, "Duplicate key 'entry'.")
+    );
+    assert_eq!(
+        parse("[package]\nentry = [\"a\"]"),
+        error(2, "Expected a string for 'entry'.")
+    );
+    assert_eq!(
+        parse("[package]\nsources = \"src\""),
+        error(2, "Expected an array of strings for 'sources'.")
+    );
+    assert_eq!(
+        parse("[package]\nentry = \"main"),
+        error(2, "Unterminated string.")
+    );
+    assert_eq!(
+        parse("[package]\nsources = [\"a\" \"b\"]"),
+        error(2, "Expected ',' or ']' in array.")
+    );
+    assert_eq!(
+        parse("[package]\nentry = main.pile"),
+        error(2, "Expected a string or an array for 'entry'.")
+    );
+    assert_eq!(
+        parse("[package]\nentry = \"a\" \"b\""),
+        error(2, "Unexpected characters after the value of 'entry'.")
+    );
+    assert_eq!(
+        parse("[package]\nentry"),
+        error(2, "Expected 'key = value'.")
+    );
+    assert_eq!(
+        parse("[dependencies]\nutils = [\"a\"]"),
+        error(2, "Expected the path of dependency 'utils'.")
+    );
+    assert_eq!(
+        parse("[package]\nname = \"x\""),
+        Err("/project/pile.toml: Missing 'entry' in [package].".to_owned())
+    );
+}
+
+#[test]
+fn test_search_path() {
+    let project =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR").to_owned() + "/tests/project");
+    let manifest = Manifest::read(&project.join("app/pile.toml")).unwrap();
+
+    assert_eq!(
+        manifest.search_path(),
+        Ok(vec![
+            project.join("app/src"),
+            project.join("app/../geometry/lib"),
+            project.join("app/../colors"),
+        ])
+    );
+}
+
+#[test]
+fn test_find() {
+    let project =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR").to_owned() + "/tests/project");
+
+    assert_eq!(
+        Manifest::find(&project.join("app/src")),
+        Some(project.join("app/pile.toml"))
+    );
+    assert_eq!(
+        Manifest::find(&project.join("geometry/lib")),
+        Some(project.join("geometry/pile.toml"))
+    );
+}
@@ -4,10 +4,11 @@ use crate::lex::Token;
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
 
+use std::fmt;
 use std::path::PathBuf;
 use std::rc::Rc;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Ast {
     pub source: Rc<ProgramSource>,
     pub expressions: Vec<Expr>,
@@ -22,7 +23,24 @@ impl ParsedAst {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum Import {
+    All,
+    Namespace(String),
+    Names(Vec<String>),
+}
+
+impl fmt::Display for Import {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Import::All => Ok(()),
+            Import::Namespace(namespace) => write!(f, " as {}", namespace),
+            Import::Names(names) => write!(f, " [{}]", names.join(" ")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
     Atom {
         line: u64,
@@ -40,7 +58,9 @@ pub enum Expr {
     },
     Use {
         line: u64,
+        file: PathBuf,
         subprogram: Ast,
+        import: Import,
     },
     Save {
         line: u64,
@@ -68,6 +88,7 @@ impl Expr {
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
     lookahead: Option<(u64, Token)>,
+    peeked: Option<Option<(u64, Token)>>,
 }
 
 impl<'a> Parser<'a> {
@@ -75,6 +96,7 @@ impl<'a> Parser<'a> {
         Parser {
             lexer,
             lookahead: None,
+            peeked: None,
         }
     }
 
@@ -115,6 +137,10 @@ impl<'a> Parser<'a> {
     fn locals(&mut self) -> Result<Vec<String>, PileError> {
         self.expect(Token::Let)?;
         self.consume()?;
+        self.identifier_list()
+    }
+
+    fn identifier_list(&mut self) -> Result<Vec<String>, PileError> {
         self.expect(Token::BracketLeft)?;
 
         let mut result = vec![];
@@ -180,12 +206,7 @@ impl<'a> Parser<'a> {
                 Some((_, Token::Begin)) => block.push(self.block()?),
                 Some((_, Token::Let)) => block.push(self.block()?),
                 Some((_, Token::Assign)) => block.push(self.assign()?),
-                Some((line, Token::Use)) => {
-                    return Err(self.parse_error(
-                        line,
-                        "'use' isn't allowed inside blocks.",
-                    ));
-                }
+                Some((_, Token::Use)) => block.push(self.using()?),
                 Some((line, _)) => block.push(Expr::Atom {
                     line,
                     token: self.lookahead.take().unwrap().1,
@@ -224,35 +245,93 @@ impl<'a> Parser<'a> {
     fn using(&mut self) -> Result<Expr, PileError> {
         self.consume()?;
 
-        match &self.lookahead {
-            Some((line, Token::String(string))) => Ok(Expr::Use {
-                line: *line,
-                subprogram: Ast {
-                    source: Rc::new(ProgramSource::File(PathBuf::from(string))),
-                    expressions: vec![],
-                },
-            }),
-            Some((line, token)) => Err(self.parse_error(
-                *line,
-                &format!("Expected string found {}.", token.error_fmt()),
-            )),
-            None => Err(self.parse_error(0, "No lookahead found.")),
-        }
+        let (line, file) = match self.lookahead.take() {
+            Some((line, Token::String(string))) => (line, string),
+            Some((line, token)) => {
+                return Err(self.parse_error(
+                    line,
+                    &format!("Expected string found {}.", token.error_fmt()),
+                ))
+            }
+            None => return Err(self.parse_error(0, "No lookahead found.")),
+        };
+
+        let import = match self.peek()? {
+            Some(Token::As) => {
+                self.consume()?;
+                self.consume()?;
+
+                match self.lookahead.take() {
+                    Some((_, Token::Identifier(namespace))) => {
+                        Import::Namespace(namespace)
+                    }
+                    Some((line, token)) => {
+                        return Err(self.parse_error(
+                            line,
+                            &format!(
+                                "Expected identifier found {}.",
+                                token.error_fmt()
+                            ),
+                        ))
+                    }
+                    None => {
+                        return Err(self.parse_error(
+                            self.lexer.line(),
+                            "Expected identifier found end of file.",
+                        ))
+                    }
+                }
+            }
+            Some(Token::BracketLeft) => {
+                self.consume()?;
+                Import::Names(self.identifier_list()?)
+            }
+            _ => Import::All,
+        };
+
+        Ok(Expr::Use {
+            line,
+            file: PathBuf::from(&file),
+            subprogram: Ast {
+                source: Rc::new(ProgramSource::File(PathBuf::from(file))),
+                expressions: vec![],
+            },
+            import,
+        })
     }
 
-    fn consume(&mut self) -> Result<(), PileError> {
-        self.lookahead = match self.lexer.next() {
+    fn next_token(&mut self) -> Result<Option<(u64, Token)>, PileError> {
+        match self.lexer.next() {
             Some(LexerItem {
                 token: Ok(Token::Comment),
                 ..
-            }) => return self.consume(),
-            Some(LexerItem { line, token, .. }) => Some((line, token?)),
-            None => None,
+            }) => self.next_token(),
+            Some(LexerItem { line, token, .. }) => Ok(Some((line, token?))),
+            None => Ok(None),
+        }
+    }
+
+    fn consume(&mut self) -> Result<(), PileError> {
+        self.lookahead = match self.peeked.take() {
+            Some(peeked) => peeked,
+            None => self.next_token()?,
         };
 
         Ok(())
     }
 
+    fn peek(&mut self) -> Result<Option<&Token>, PileError> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next_token()?);
+        }
+
+        Ok(self
+            .peeked
+            .as_ref()
+            .and_then(|peeked| peeked.as_ref())
+            .map(|(_, token)| token))
+    }
+
     fn expect(&mut self, expected: Token) -> Result<(), PileError> {
         match &self.lookahead {
             Some((line, current_token)) => {
@@ -381,12 +381,14 @@ use \"file_c\"
             expressions: vec![
                 Expr::Use {
                     line: 2,
+                    file: PathBuf::from("file_a"),
                     subprogram: Ast {
                         source: Rc::new(ProgramSource::File(PathBuf::from(
                             "file_a",
                         ))),
                         expressions: vec![],
                     },
+                    import: Import::All,
                 },
                 Expr::Atom {
                     line: 3,
@@ -406,21 +408,25 @@ use \"file_c\"
                 },
                 Expr::Use {
                     line: 4,
+                    file: PathBuf::from("file_b"),
                     subprogram: Ast {
                         source: Rc::new(ProgramSource::File(PathBuf::from(
                             "file_b",
                         ))),
                         expressions: vec![],
                     },
+                    import: Import::All,
                 },
                 Expr::Use {
                     line: 5,
+                    file: PathBuf::from("file_c"),
                     subprogram: Ast {
                         source: Rc::new(ProgramSource::File(PathBuf::from(
                             "file_c",
                         ))),
                         expressions: vec![],
                     },
+                    import: Import::All,
                 },
                 Expr::Atom {
                     line: 6,
@@ -440,18 +446,32 @@ use \"file_c\"
 }
 
 #[test]
-fn test_error_use_in_block() {
-    expect_error(
+fn test_use_in_block() {
+    expect_ast(
         "
 begin
-    use \"file1\"
+    use \"file1\" as f
 end
 ",
-        Err(PileError::in_line(
-            Rc::new(ProgramSource::Stdin),
-            3,
-            "'use' isn't allowed inside blocks.".to_owned(),
-        )),
+        Ast {
+            source: Rc::new(ProgramSource::Stdin),
+            expressions: vec![Expr::Block {
+                begin: 2,
+                end: 4,
+                locals: vec![],
+                expressions: Rc::new(vec![Expr::Use {
+                    line: 3,
+                    file: PathBuf::from("file1"),
+                    subprogram: Ast {
+                        source: Rc::new(ProgramSource::File(PathBuf::from(
+                            "file1",
+                        ))),
+                        expressions: vec![],
+                    },
+                    import: Import::Namespace("f".to_owned()),
+                }]),
+            }],
+        },
     )
 }
 
@@ -580,3 +600,69 @@ fn test_error_bad_assign3() {
         )),
     )
 }
+
+#[test]
+fn test_use_import() {
+    let use_expr = |line, file: &str, import| Expr::Use {
+        line,
+        file: PathBuf::from(file),
+        subprogram: Ast {
+            source: Rc::new(ProgramSource::File(PathBuf::from(file))),
+            expressions: vec![],
+        },
+        import,
+    };
+
+    expect_ast(
+        "
+use \"math\" as m
+use \"strings\" [join split] # comment
+use \"plain\"
+m.sqrt
+",
+        Ast {
+            source: Rc::new(ProgramSource::Stdin),
+            expressions: vec![
+                use_expr(2, "math", Import::Namespace("m".to_owned())),
+                use_expr(
+                    3,
+                    "strings",
+                    Import::Names(vec!["join".to_owned(), "split".to_owned()]),
+                ),
+                use_expr(4, "plain", Import::All),
+                Expr::Atom {
+                    line: 5,
+                    token: Token::Identifier("m.sqrt".to_owned()),
+                },
+            ],
+        },
+    )
+}
+
+#[test]
+fn test_error_use_as() {
+    expect_error(
+        "use \"math\" as 1",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected identifier found natural '1'.".to_owned(),
+        )),
+    );
+    expect_error(
+        "use \"math\" as",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected identifier found end of file.".to_owned(),
+        )),
+    );
+    expect_error(
+        "use \"math\" [a 1]",
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::Stdin),
+            1,
+            "Expected identifier found natural '1'.".to_owned(),
+        )),
+    );
+}
@@ -9,6 +9,7 @@ pub struct PileError {
     source: Rc<ProgramSource>,
     lines: (u64, u64),
     message: String,
+    status: Option<i32>,
 }
 
 impl PileError {
@@ -21,6 +22,7 @@ impl PileError {
             source,
             lines,
             message,
+            status: None,
         }
     }
 
@@ -33,6 +35,7 @@ impl PileError {
             source,
             lines: (line, line),
             message,
+            status: None,
         }
     }
 
@@ -41,8 +44,28 @@ impl PileError {
             source,
             lines: (0, 0),
             message,
+            status: None,
         }
     }
+
+    // NOTE: 'exit' ends the program like an error, so the REPL and the test
+    // runner can keep going.
+    pub fn exit(
+        source: Rc<ProgramSource>,
+        lines: (u64, u64),
+        status: i32,
+    ) -> Self {
+        PileError {
+            source,
+            lines,
+            message: format!("Exited with status {}", status),
+            status: Some(status),
+        }
+    }
+
+    pub fn exit_status(&self) -> Option<i32> {
+        self.status
+    }
 }
 
 impl Error for PileError {}
@@ -51,6 +74,7 @@ impl fmt::Display for PileError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.source.as_ref() {
             ProgramSource::Repl | ProgramSource::Stdin => (),
+            ProgramSource::Inline => write!(f, "<inline>:")?,
             ProgramSource::File(file) => {
                 write!(f, "{}:", file.to_string_lossy())?;
             }
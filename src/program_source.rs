@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub enum ProgramSource {
     Repl,
     Stdin,
+    Inline,
     File(PathBuf),
 }
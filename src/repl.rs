@@ -1,31 +1,85 @@
 use crate::completion;
-use crate::interpret::Interpreter;
+use crate::formatting;
+use crate::interpret::{Interpreter, RuntimeValue};
 use crate::lex::Lexer;
 use crate::locals;
 use crate::parse::Parser;
+use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
 use crate::using;
 
+use std::borrow::Cow;
 use std::cell::{Ref, RefCell};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use rustyline::completion::{extract_word, Completer};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
-use rustyline::validate::Validator;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
 use rustyline::{CompletionType, Config, EditMode, Editor};
 use rustyline_derive::Helper;
 
+mod highlight;
+mod validation;
+use validation::InputState;
+
 #[derive(Helper)]
 struct ReplHelper<'i> {
     interpreter: Option<Ref<'i, Interpreter>>,
 }
 
-impl<'i> Validator for ReplHelper<'i> {}
-impl<'i> Highlighter for ReplHelper<'i> {}
-impl<'i> Hinter for ReplHelper<'i> {}
+impl<'i> Validator for ReplHelper<'i> {
+    fn validate(
+        &self,
+        ctx: &mut ValidationContext,
+    ) -> rustyline::Result<ValidationResult> {
+        Ok(match validation::input_state(ctx.input()) {
+            InputState::Complete => ValidationResult::Valid(None),
+            InputState::Incomplete => ValidationResult::Incomplete,
+        })
+    }
+}
+impl<'i> Highlighter for ReplHelper<'i> {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        if line.trim_start().starts_with(':') {
+            return Cow::Borrowed(line);
+        }
+
+        Cow::Owned(highlight::highlight(line, pos, |ident| {
+            match &self.interpreter {
+                Some(interpreter) => interpreter.is_bound(ident),
+                None => false,
+            }
+        }))
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("{}{}\x1b[0m", highlight::HINT, hint))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        // NOTE: the matching block depends on the cursor position
+        true
+    }
+}
+impl<'i> Hinter for ReplHelper<'i> {
+    fn hint(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+
+        let (_, word) = extract_word(line, pos, None, b" \t");
+        completion::operator_hint(word)
+    }
+}
 impl<'i> Completer for ReplHelper<'i> {
     type Candidate = String;
 
@@ -35,7 +89,7 @@ impl<'i> Completer for ReplHelper<'i> {
         pos: usize,
         _ctx: &rustyline::Context<'_>,
     ) -> rustyline::Result<(usize, Vec<Self::Candidate>)> {
-        let (start, word) = extract_word(line, pos, None, &[b' ', b'\t']);
+        let (start, word) = extract_word(line, pos, None, b" \t");
         if let Some(interpreter) = &self.interpreter {
             Ok((
                 start,
@@ -47,6 +101,136 @@ impl<'i> Completer for ReplHelper<'i> {
     }
 }
 
+const COMMAND_HELP: &str = "\
+:stack               show the whole stack
+:vars                show all variables and their values
+:load <file>         evaluate a file in the current session
+:reset               clear the stack and all variables
+:save <file>         write the code of this session to a file
+:snapshot <file>     write the stack and all variables to a file
+:restore <file>      replace the session with a snapshot
+:trace on|off        enable or disable program tracing
+:help [operator]     show this help or describe an operator";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Stack,
+    Vars,
+    Load(PathBuf),
+    Reset,
+    Save(PathBuf),
+    Snapshot(PathBuf),
+    Restore(PathBuf),
+    Trace(bool),
+    Help(Option<String>),
+}
+
+impl Command {
+    fn parse(command: &str) -> Result<Command, String> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arguments: Vec<&str> = words.collect();
+
+        match (name, arguments.as_slice()) {
+            ("stack", []) => Ok(Command::Stack),
+            ("vars", []) => Ok(Command::Vars),
+            ("load", [file]) => Ok(Command::Load(PathBuf::from(file))),
+            ("reset", []) => Ok(Command::Reset),
+            ("save", [file]) => Ok(Command::Save(PathBuf::from(file))),
+            ("snapshot", [file]) => Ok(Command::Snapshot(PathBuf::from(file))),
+            ("restore", [file]) => Ok(Command::Restore(PathBuf::from(file))),
+            ("load", [])
+            | ("save", [])
+            | ("snapshot", [])
+            | ("restore", []) => Err(format!("Missing file for ':{}'.", name)),
+            ("trace", ["on"]) => Ok(Command::Trace(true)),
+            ("trace", ["off"]) => Ok(Command::Trace(false)),
+            ("trace", []) | ("trace", [_]) => {
+                Err("Expected 'on' or 'off' for ':trace'.".to_owned())
+            }
+            ("help", []) => Ok(Command::Help(None)),
+            ("help", [operator]) => {
+                Ok(Command::Help(Some((*operator).to_owned())))
+            }
+            ("stack", _)
+            | ("vars", _)
+            | ("load", _)
+            | ("reset", _)
+            | ("trace", _)
+            | ("help", _) => {
+                Err(format!("Too many arguments for ':{}'.", name))
+            }
+            (name, _) => {
+                Err(format!("Unknown command ':{}'. Try ':help'.", name))
+            }
+        }
+    }
+
+    fn run(
+        self,
+        interpreter: &mut Interpreter,
+        search_path: &[PathBuf],
+    ) -> Result<Option<i32>, String> {
+        match self {
+            Command::Stack => interpreter.show_stack()?,
+            Command::Vars => interpreter.show_variables()?,
+            Command::Load(file) => {
+                let ast = using::load(&file, search_path)
+                    .map_err(|e| e.to_string())?;
+                return Ok(print_result(interpreter.eval(ast)));
+            }
+            Command::Reset => interpreter.reset(),
+            Command::Save(file) => {
+                let program =
+                    formatting::format_ast(interpreter.ast().as_ref())
+                        .map_err(|e| e.to_string())?;
+                write_file(&file, &program)?;
+            }
+            Command::Snapshot(file) => {
+                let snapshot =
+                    interpreter.snapshot().map_err(|e| e.to_string())?;
+                write_file(&file, &snapshot)?;
+            }
+            Command::Restore(file) => {
+                let ast = using::load(&file, search_path)
+                    .map_err(|e| e.to_string())?;
+                interpreter.reset();
+                return Ok(print_result(interpreter.eval(ast)));
+            }
+            Command::Trace(trace) => interpreter.set_trace(trace),
+            Command::Help(None) => println!("{}", COMMAND_HELP),
+            Command::Help(Some(operator)) => {
+                let info =
+                    completion::operator_info(&operator).ok_or_else(|| {
+                        format!("Unknown operator '{}'", operator)
+                    })?;
+                println!("{}", info);
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn write_file(file: &Path, content: &str) -> Result<(), String> {
+    fs::write(file, content)
+        .map_err(|err| format!("{}: {}", file.to_string_lossy(), err))
+}
+
+// NOTE: Returns the status if the program called 'exit'.
+fn print_result(
+    result: Result<Option<&RuntimeValue>, PileError>,
+) -> Option<i32> {
+    match result {
+        Ok(Some(value)) => println!("{}", value),
+        Ok(None) => println!(),
+        Err(err) if err.exit_status().is_some() => return err.exit_status(),
+        Err(msg) => eprintln!("{}", msg),
+    };
+
+    None
+}
+
 fn pile_history() -> PathBuf {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
 
@@ -91,8 +275,11 @@ fn get_line<'i>(
     line
 }
 
-pub fn repl() -> ! {
+pub fn repl(search_path: &[PathBuf], args: &[String], strict: bool) -> ! {
     let interpreter = RefCell::new(Interpreter::empty());
+    interpreter.borrow_mut().set_search_path(search_path);
+    interpreter.borrow_mut().set_args(args);
+    interpreter.borrow_mut().set_strict(strict);
     let mut editor = create_editor();
     let exit_code;
 
@@ -105,6 +292,20 @@ pub fn repl() -> ! {
             }
         };
 
+        if let Some(command) = line.trim_start().strip_prefix(':') {
+            match Command::parse(command).and_then(|command| {
+                command.run(&mut interpreter.borrow_mut(), search_path)
+            }) {
+                Ok(Some(status)) => {
+                    exit_code = status;
+                    break;
+                }
+                Ok(None) => (),
+                Err(msg) => eprintln!("{}", msg),
+            }
+            continue;
+        }
+
         let lexer = Lexer::new(&line, Rc::new(ProgramSource::Repl));
         let parser = Parser::new(lexer);
         let ast = match parser.parse() {
@@ -115,7 +316,7 @@ pub fn repl() -> ! {
             }
         };
 
-        let ast = match using::resolve(locals::translate(ast)) {
+        let ast = match using::resolve(locals::translate(ast), search_path) {
             Ok(ast) => ast,
             Err(msg) => {
                 eprintln!("{}", msg);
@@ -123,11 +324,10 @@ pub fn repl() -> ! {
             }
         };
 
-        match interpreter.borrow_mut().eval(ast) {
-            Ok(Some(value)) => println!("{}", value),
-            Ok(None) => println!(),
-            Err(msg) => eprintln!("{}", msg),
-        };
+        if let Some(status) = print_result(interpreter.borrow_mut().eval(ast)) {
+            exit_code = status;
+            break;
+        }
     }
 
     if let Err(err) = editor.save_history(&pile_history()) {
@@ -140,3 +340,6 @@ pub fn repl() -> ! {
 
     std::process::exit(exit_code);
 }
+
+#[cfg(test)]
+mod test;
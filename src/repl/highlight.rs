@@ -0,0 +1,135 @@
+use crate::lex::{Lexer, LexerItem, Operator, Token};
+use crate::program_source::ProgramSource;
+
+use std::rc::Rc;
+
+const RESET: &str = "\x1b[0m";
+const KEYWORD: &str = "\x1b[1;35m";
+const OPERATOR: &str = "\x1b[36m";
+const NUMBER: &str = "\x1b[33m";
+const STRING: &str = "\x1b[32m";
+const BOOLEAN: &str = "\x1b[1;33m";
+const COMMENT: &str = "\x1b[90m";
+const IDENTIFIER: &str = "\x1b[34m";
+const ERROR: &str = "\x1b[1;31m";
+const MATCHING: &str = "\x1b[7m";
+pub const HINT: &str = "\x1b[90m";
+
+struct Span {
+    start: usize,
+    end: usize,
+    color: Option<&'static str>,
+    token: Option<Token>,
+}
+
+fn spans<K>(line: &str, is_known: K) -> Vec<Span>
+where
+    K: Fn(&str) -> bool,
+{
+    let mut spans = vec![];
+    let mut offset = 0;
+
+    for LexerItem { token, lexeme, .. } in
+        Lexer::new(line, Rc::new(ProgramSource::Repl))
+    {
+        let rest = &line[offset..];
+        let start = offset + rest.len() - rest.trim_start().len();
+        let end = start + lexeme.len();
+        offset = end;
+
+        let color = match &token {
+            Err(_) => Some(ERROR),
+            Ok(Token::Begin)
+            | Ok(Token::End)
+            | Ok(Token::Let)
+            | Ok(Token::Use)
+            | Ok(Token::As)
+            | Ok(Token::Assign)
+            | Ok(Token::BracketLeft)
+            | Ok(Token::BracketRight) => Some(KEYWORD),
+            Ok(Token::Operator(_)) => Some(OPERATOR),
+            Ok(Token::Number(_)) => Some(NUMBER),
+            Ok(Token::String(_)) => Some(STRING),
+            Ok(Token::Boolean(_)) => Some(BOOLEAN),
+            Ok(Token::Comment) => Some(COMMENT),
+            Ok(Token::Identifier(ident)) if is_known(ident) => Some(IDENTIFIER),
+            Ok(Token::Identifier(ident))
+                if Operator::from_name(ident).is_some() =>
+            {
+                Some(OPERATOR)
+            }
+            Ok(Token::Identifier(_)) => None,
+        };
+
+        spans.push(Span {
+            start,
+            end,
+            color,
+            token: token.ok(),
+        });
+    }
+
+    spans
+}
+
+// NOTE: Returns the indices of the 'end' under the cursor and its matching
+// 'begin' or 'let'.
+fn matching_block(spans: &[Span], pos: usize) -> Option<(usize, usize)> {
+    let mut open_blocks = vec![];
+
+    for (index, span) in spans.iter().enumerate() {
+        match span.token {
+            Some(Token::Begin) | Some(Token::Let) => open_blocks.push(index),
+            Some(Token::End) => {
+                let open = open_blocks.pop();
+                if span.start <= pos && pos <= span.end {
+                    return open.map(|open| (open, index));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+pub fn highlight<K>(line: &str, pos: usize, is_known: K) -> String
+where
+    K: Fn(&str) -> bool,
+{
+    let spans = spans(line, is_known);
+    let matching = matching_block(&spans, pos);
+    let mut highlighted = String::with_capacity(line.len() * 2);
+    let mut offset = 0;
+
+    for (index, span) in spans.iter().enumerate() {
+        highlighted.push_str(&line[offset..span.start]);
+
+        let is_matching = matches!(
+            matching,
+            Some((open, end)) if open == index || end == index
+        );
+
+        if is_matching {
+            highlighted.push_str(MATCHING);
+        }
+        if let Some(color) = span.color {
+            highlighted.push_str(color);
+        }
+
+        highlighted.push_str(&line[span.start..span.end]);
+
+        if is_matching || span.color.is_some() {
+            highlighted.push_str(RESET);
+        }
+
+        offset = span.end;
+    }
+
+    highlighted.push_str(&line[offset..]);
+
+    highlighted
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,99 @@
+use super::*;
+
+fn plain(line: &str) -> String {
+    highlight(line, 0, |_| false)
+}
+
+#[test]
+fn test_highlight_tokens() {
+    assert_eq!(
+        plain("begin 1 end"),
+        format!(
+            "{k}begin{r} {n}1{r} {k}end{r}",
+            k = KEYWORD,
+            n = NUMBER,
+            r = RESET
+        )
+    );
+    assert_eq!(
+        plain("\"hi\"  print # done"),
+        format!(
+            "{s}\"hi\"{r}  {o}print{r} {c}# done{r}",
+            s = STRING,
+            o = OPERATOR,
+            c = COMMENT,
+            r = RESET
+        )
+    );
+    assert_eq!(
+        plain("true -> x"),
+        format!("{b}true{r} {k}->{r} x", b = BOOLEAN, k = KEYWORD, r = RESET)
+    );
+}
+
+#[test]
+fn test_highlight_identifiers() {
+    assert_eq!(
+        highlight("known unknown", 0, |ident| ident == "known"),
+        format!("{}known{} unknown", IDENTIFIER, RESET)
+    );
+}
+
+#[test]
+fn test_highlight_builtin_identifiers() {
+    assert_eq!(
+        plain("\"hi\" println"),
+        format!("{}\"hi\"{} {}println{}", STRING, RESET, OPERATOR, RESET)
+    );
+    assert_eq!(
+        highlight("println", 0, |ident| ident == "println"),
+        format!("{}println{}", IDENTIFIER, RESET)
+    );
+}
+
+#[test]
+fn test_highlight_errors() {
+    assert_eq!(
+        plain("1 $ 2"),
+        format!("{n}1{r} {e}${r} {n}2{r}", n = NUMBER, e = ERROR, r = RESET)
+    );
+    assert_eq!(plain("\"open"), format!("{}\"open{}", ERROR, RESET));
+}
+
+#[test]
+fn test_highlight_keeps_text() {
+    let line = "let [a b]\n  a   b +\nend -> f # ö \"x\"\n$";
+    let highlighted = highlight(line, 5, |_| true);
+    let mut stripped = String::new();
+    let mut chars = highlighted.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+
+    assert_eq!(stripped, line);
+}
+
+#[test]
+fn test_matching_block() {
+    let line = "let [x] begin x end end";
+    let block_spans = spans(line, |_| false);
+
+    // cursor on the inner 'end'
+    assert_eq!(matching_block(&block_spans, 17), Some((4, 6)));
+    // cursor right after the outer 'end'
+    assert_eq!(matching_block(&block_spans, line.len()), Some((0, 7)));
+    // cursor on the 'begin'
+    assert_eq!(matching_block(&block_spans, 9), None);
+    // unmatched 'end'
+    assert_eq!(matching_block(&spans(" end", |_| false), 2), None);
+
+    assert!(highlight(line, 17, |_| false)
+        .starts_with(&format!("{}let{}", KEYWORD, RESET)));
+    assert!(highlight(line, 17, |_| false)
+        .contains(&format!("{}{}begin{}", MATCHING, KEYWORD, RESET)));
+}
@@ -0,0 +1,125 @@
+use super::*;
+
+#[test]
+fn test_parse_command() {
+    assert_eq!(Command::parse("stack"), Ok(Command::Stack));
+    assert_eq!(Command::parse(" vars "), Ok(Command::Vars));
+    assert_eq!(
+        Command::parse("load lib.pile"),
+        Ok(Command::Load(PathBuf::from("lib.pile")))
+    );
+    assert_eq!(Command::parse("reset"), Ok(Command::Reset));
+    assert_eq!(
+        Command::parse("save session.pile"),
+        Ok(Command::Save(PathBuf::from("session.pile")))
+    );
+    assert_eq!(
+        Command::parse("snapshot snap.pile"),
+        Ok(Command::Snapshot(PathBuf::from("snap.pile")))
+    );
+    assert_eq!(
+        Command::parse("restore snap.pile"),
+        Ok(Command::Restore(PathBuf::from("snap.pile")))
+    );
+    assert_eq!(Command::parse("trace on"), Ok(Command::Trace(true)));
+    assert_eq!(Command::parse("trace off"), Ok(Command::Trace(false)));
+    assert_eq!(Command::parse("help"), Ok(Command::Help(None)));
+    assert_eq!(
+        Command::parse("help dup"),
+        Ok(Command::Help(Some("dup".to_owned())))
+    );
+}
+
+#[test]
+fn test_parse_command_errors() {
+    assert_eq!(
+        Command::parse("load"),
+        Err("Missing file for ':load'.".to_owned())
+    );
+    assert_eq!(
+        Command::parse("snapshot"),
+        Err("Missing file for ':snapshot'.".to_owned())
+    );
+    assert_eq!(
+        Command::parse("trace maybe"),
+        Err("Expected 'on' or 'off' for ':trace'.".to_owned())
+    );
+    assert_eq!(
+        Command::parse("stack 1"),
+        Err("Too many arguments for ':stack'.".to_owned())
+    );
+    assert_eq!(
+        Command::parse("quit"),
+        Err("Unknown command ':quit'. Try ':help'.".to_owned())
+    );
+}
+
+#[test]
+fn test_run_command() {
+    let mut interpreter = Interpreter::empty();
+    let file = env!("CARGO_MANIFEST_DIR").to_owned()
+        + "/src/interpret/file_test/proj_simple/util.pile";
+
+    Command::Load(PathBuf::from(file))
+        .run(&mut interpreter, &[])
+        .unwrap();
+    assert_eq!(interpreter.ast().as_ref().expressions.len(), 1);
+
+    Command::Reset.run(&mut interpreter, &[]).unwrap();
+    assert!(interpreter.ast().as_ref().expressions.is_empty());
+
+    assert!(Command::Help(Some("unknown".to_owned()))
+        .run(&mut interpreter, &[])
+        .is_err());
+}
+
+#[test]
+fn test_save_loaded_file() {
+    let mut interpreter = Interpreter::empty();
+
+    Command::Load(PathBuf::from("src/interpret/file_test/proj_simple/util"))
+        .run(&mut interpreter, &[])
+        .unwrap();
+
+    assert_eq!(
+        formatting::format_ast(interpreter.ast().as_ref()).unwrap(),
+        "use \"src/interpret/file_test/proj_simple/util\"\n"
+    );
+}
+
+#[test]
+fn test_save_standard_library() {
+    let parse = |text: &str, source| {
+        let lexer = Lexer::new(text, Rc::new(source));
+        let ast = locals::translate(Parser::new(lexer).parse().unwrap());
+        using::resolve(ast, &[])
+    };
+    let mut interpreter = Interpreter::empty();
+
+    interpreter
+        .eval(parse("use \"std/math\" as m", ProgramSource::Repl).unwrap())
+        .unwrap();
+    let program = formatting::format_ast(interpreter.ast().as_ref()).unwrap();
+
+    assert_eq!(program, "use \"std/math\" as m\n");
+    assert!(parse(&program, ProgramSource::Stdin).is_ok());
+}
+
+#[test]
+fn test_save_successful_lines() {
+    let parse = |text: &str| {
+        let lexer = Lexer::new(text, Rc::new(ProgramSource::Repl));
+        let ast = locals::translate(Parser::new(lexer).parse().unwrap());
+        using::resolve(ast, &[]).unwrap()
+    };
+    let mut interpreter = Interpreter::empty();
+
+    interpreter.eval(parse("begin 2 * end -> dbl")).unwrap();
+    assert!(interpreter.eval(parse("2 * 3 dbl")).is_err());
+    interpreter.eval(parse("3 dbl")).unwrap();
+
+    assert_eq!(
+        formatting::format_ast(interpreter.ast().as_ref()).unwrap(),
+        "begin 2 * end -> dbl\n3 dbl\n"
+    );
+}
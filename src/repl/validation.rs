@@ -0,0 +1,75 @@
+use crate::lex::{Lexer, LexerItem, Token};
+use crate::program_source::ProgramSource;
+
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+pub enum InputState {
+    Complete,
+    Incomplete,
+}
+
+// NOTE: Everything that isn't obviously unfinished is complete. The parser
+// reports the remaining errors once the input is submitted.
+pub fn input_state(input: &str) -> InputState {
+    if input.trim_start().starts_with(':') {
+        return InputState::Complete;
+    }
+
+    let mut depth = 0u64;
+    let mut in_header = false;
+    let mut lexer = Lexer::new(input, Rc::new(ProgramSource::Repl)).peekable();
+
+    while let Some(LexerItem { token, lexeme, .. }) = lexer.next() {
+        let token = match token {
+            Ok(token) => token,
+            Err(_) if lexer.peek().is_none() && is_open_string(&lexeme) => {
+                return InputState::Incomplete
+            }
+            Err(_) => return InputState::Complete,
+        };
+
+        match token {
+            Token::Let => {
+                depth += 1;
+                in_header = true;
+            }
+            Token::Begin => depth += 1,
+            Token::End => match depth.checked_sub(1) {
+                Some(new_depth) => depth = new_depth,
+                None => return InputState::Complete,
+            },
+            Token::BracketRight => in_header = false,
+            Token::BracketLeft | Token::Identifier(_) | Token::Comment => (),
+            _ if in_header => return InputState::Complete,
+            _ => (),
+        }
+    }
+
+    if depth > 0 || in_header {
+        InputState::Incomplete
+    } else {
+        InputState::Complete
+    }
+}
+
+fn is_open_string(lexeme: &str) -> bool {
+    let mut chars = lexeme.chars();
+
+    if chars.next() != Some('"') {
+        return false;
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.next().is_none() => return true,
+            '"' => return false,
+            _ => (),
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test;
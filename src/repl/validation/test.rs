@@ -0,0 +1,37 @@
+use super::*;
+
+#[test]
+fn test_complete_input() {
+    assert_eq!(input_state(""), InputState::Complete);
+    assert_eq!(input_state("1 2 + print"), InputState::Complete);
+    assert_eq!(input_state("begin 1 end -> one"), InputState::Complete);
+    assert_eq!(input_state("let [a b] a b + end"), InputState::Complete);
+    assert_eq!(input_state("\"a string\""), InputState::Complete);
+    assert_eq!(input_state("\"escaped \\\" quote\""), InputState::Complete);
+    assert_eq!(input_state(":load file.pile"), InputState::Complete);
+}
+
+#[test]
+fn test_incomplete_input() {
+    assert_eq!(input_state("begin"), InputState::Incomplete);
+    assert_eq!(input_state("let [n]\n  n 1 +"), InputState::Incomplete);
+    assert_eq!(
+        input_state("begin\n  begin 1 end\n  # end"),
+        InputState::Incomplete
+    );
+    assert_eq!(input_state("let [a b"), InputState::Incomplete);
+    assert_eq!(input_state("let [a\n# comment\nb"), InputState::Incomplete);
+    assert_eq!(input_state("\"unterminated"), InputState::Incomplete);
+    assert_eq!(input_state("\"line\\"), InputState::Incomplete);
+    assert_eq!(input_state("begin \"a\nb"), InputState::Incomplete);
+}
+
+#[test]
+fn test_invalid_input_is_complete() {
+    // NOTE: the parser reports these errors after submitting the input
+    assert_eq!(input_state("end"), InputState::Complete);
+    assert_eq!(input_state("begin 1 end end begin"), InputState::Complete);
+    assert_eq!(input_state("let [a 1] end"), InputState::Complete);
+    assert_eq!(input_state("begin $"), InputState::Complete);
+    assert_eq!(input_state("\"\\q\""), InputState::Complete);
+}
@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+include!(concat!(env!("OUT_DIR"), "/stdlib.rs"));
+
+// NOTE: 'use "std/name"' always refers to the bundled module. Inside the
+// standard library every 'use' is relative to the virtual directory.
+const PREFIX: &str = "std";
+const DIRECTORY: &str = "<std>";
+
+// NOTE: Builtins that used to be defined by a bundled module. Imports of the
+// module still offer them, so 'use "std/math" [max]' keeps working.
+const REEXPORTS: &[(&str, &[&str])] = &[("math", &["max", "min", "abs"])];
+
+pub fn find(
+    file: &Path,
+    current_dir: &Path,
+) -> Option<Result<PathBuf, String>> {
+    let name = match file.strip_prefix(PREFIX) {
+        Ok(name) => name,
+        Err(_) if current_dir == Path::new(DIRECTORY) => file,
+        Err(_) => return None,
+    };
+    let name = name.with_extension("");
+
+    Some(
+        match MODULES.iter().find(|(module, _)| Path::new(module) == name) {
+            Some((module, _)) => {
+                Ok(Path::new(DIRECTORY).join(module).with_extension("pile"))
+            }
+            None => Err(format!(
+                "'{}' isn't part of the standard library. Available modules: \
+                 {}",
+                file.to_string_lossy(),
+                MODULES
+                    .iter()
+                    .map(|(module, _)| format!("{}/{}", PREFIX, module))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        },
+    )
+}
+
+// NOTE: The name of a bundled module as it is written after 'use'.
+pub fn name(path: &Path) -> Option<PathBuf> {
+    let name = path.strip_prefix(DIRECTORY).ok()?;
+
+    Some(Path::new(PREFIX).join(name))
+}
+
+pub fn source(path: &Path) -> Option<&'static str> {
+    let name = path.strip_prefix(DIRECTORY).ok()?.with_extension("");
+
+    MODULES
+        .iter()
+        .find(|(module, _)| Path::new(module) == name)
+        .map(|(_, source)| *source)
+}
+
+pub fn reexports(path: &Path) -> &'static [&'static str] {
+    let name = match path.strip_prefix(DIRECTORY) {
+        Ok(name) => name.with_extension(""),
+        Err(_) => return &[],
+    };
+
+    REEXPORTS
+        .iter()
+        .find(|(module, _)| Path::new(module) == name)
+        .map_or(&[], |(_, names)| *names)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,75 @@
+use super::*;
+use crate::interpret::Interpreter;
+use crate::lex::Lexer;
+use crate::locals;
+use crate::parse::Parser;
+use crate::program_source::ProgramSource;
+use crate::using;
+
+use std::fs;
+use std::rc::Rc;
+
+fn run_test_program(path: PathBuf) {
+    let program = fs::read_to_string(&path).unwrap();
+    let lexer =
+        Lexer::new(program.as_ref(), Rc::new(ProgramSource::File(path)));
+    let parser = Parser::new(lexer);
+    let ast = parser.parse().unwrap();
+    let ast = locals::translate(ast);
+    let ast = using::resolve(ast, &[]).unwrap();
+
+    let mut interpreter = Interpreter::new(ast, 100, false);
+    interpreter.run().expect("Test program failed!");
+}
+
+#[test]
+fn run_test_programs() {
+    let test_dir =
+        env!("CARGO_MANIFEST_DIR").to_owned() + "/src/stdlib/test_programs/";
+    let files = fs::read_dir(test_dir).unwrap();
+
+    files
+        .map(Result::unwrap)
+        .map(|file| file.path())
+        .filter(|path| path.extension().unwrap_or_default() == "pile")
+        .map(run_test_program)
+        .for_each(drop);
+}
+
+#[test]
+fn test_find() {
+    assert_eq!(
+        find(Path::new("std/math"), Path::new(".")),
+        Some(Ok(PathBuf::from("<std>/math.pile")))
+    );
+    assert_eq!(
+        find(Path::new("string"), Path::new("<std>")),
+        Some(Ok(PathBuf::from("<std>/string.pile")))
+    );
+    assert_eq!(find(Path::new("math"), Path::new(".")), None);
+    assert_eq!(find(Path::new("stdlib/math"), Path::new(".")), None);
+    assert!(matches!(
+        find(Path::new("std/unknown"), Path::new(".")),
+        Some(Err(msg)) if msg.starts_with("'std/unknown' isn't part of")
+    ));
+}
+
+#[test]
+fn test_source() {
+    assert_eq!(
+        source(Path::new("<std>/math.pile")),
+        Some(include_str!("math.pile"))
+    );
+    assert_eq!(source(Path::new("std/math.pile")), None);
+    assert_eq!(source(Path::new("math.pile")), None);
+}
+
+#[test]
+fn test_reexports() {
+    assert_eq!(
+        reexports(Path::new("<std>/math.pile")),
+        &["max", "min", "abs"]
+    );
+    assert!(reexports(Path::new("<std>/string.pile")).is_empty());
+    assert!(reexports(Path::new("math.pile")).is_empty());
+}
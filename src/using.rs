@@ -1,19 +1,25 @@
 use crate::lex::Lexer;
+use crate::lex::Token;
 use crate::locals;
 use crate::locals::ScopedAst;
 use crate::parse::Ast;
 use crate::parse::Expr;
+use crate::parse::Import;
 use crate::parse::Parser;
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
+use crate::stdlib;
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::mem;
+use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 #[derive(Debug, PartialEq)]
-pub struct ResolvedAst(Ast);
+pub struct ResolvedAst(Ast, ModuleCache);
 
 impl ResolvedAst {
     #[allow(dead_code)] // used in the tests
@@ -22,15 +28,26 @@ impl ResolvedAst {
     }
 
     pub fn repl_ast() -> ResolvedAst {
-        ResolvedAst(Ast {
-            source: Rc::new(ProgramSource::Repl),
-            expressions: vec![],
-        })
+        ResolvedAst(
+            Ast {
+                source: Rc::new(ProgramSource::Repl),
+                expressions: vec![],
+            },
+            ModuleCache::default(),
+        )
     }
 
     pub fn append(&mut self, mut other: ResolvedAst) {
         self.0.expressions.append(&mut other.0.expressions)
     }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.0.expressions.truncate(len)
+    }
+
+    pub fn take_module_cache(&mut self) -> ModuleCache {
+        mem::take(&mut self.1)
+    }
 }
 
 impl AsRef<Ast> for ResolvedAst {
@@ -71,7 +88,29 @@ impl<'d> DependencyTree<'d> {
     }
 }
 
-pub fn resolve(ast: ScopedAst) -> Result<ResolvedAst, PileError> {
+// NOTE: Every module is read and resolved once per run. Later uses of the same
+// module share its body, which the interpreter only runs the first time.
+#[derive(Debug, PartialEq)]
+struct Module {
+    body: Ast,
+    bindings: Vec<(String, String)>,
+}
+
+// NOTE: Maps the canonical path of every module used by a program to the
+// module. The interpreter keeps it for the modules loaded at runtime.
+#[derive(Debug, Default, PartialEq)]
+pub struct ModuleCache(HashMap<PathBuf, Module>);
+
+struct Resolver<'s> {
+    search_path: &'s [PathBuf],
+    root: PathBuf,
+    modules: HashMap<PathBuf, Module>,
+}
+
+pub fn resolve(
+    ast: ScopedAst,
+    search_path: &[PathBuf],
+) -> Result<ResolvedAst, PileError> {
     let path = match ast.as_ref().source.as_ref() {
         ProgramSource::File(file) => normalize_path(file).map_err(|err| {
             PileError::in_file(Rc::clone(&ast.as_ref().source), err)
@@ -80,84 +119,555 @@ pub fn resolve(ast: ScopedAst) -> Result<ResolvedAst, PileError> {
     };
 
     let dir = match ast.as_ref().source.as_ref() {
-        ProgramSource::Repl | ProgramSource::Stdin => PathBuf::from("."),
-        ProgramSource::File(file) => file
-            .parent()
-            .map(&Path::to_owned)
-            .unwrap_or_else(|| PathBuf::from(".")),
+        ProgramSource::Repl | ProgramSource::Stdin | ProgramSource::Inline => {
+            PathBuf::from(".")
+        }
+        ProgramSource::File(file) => directory_of(file),
     };
 
-    resolve_use(&dir, &DependencyTree::new(&path), ast)
+    let mut resolver = Resolver::new(search_path, &dir);
+    let ast = resolver.resolve_use(&dir, &DependencyTree::new(&path), ast)?;
+
+    Ok(ResolvedAst(ast, ModuleCache(resolver.modules)))
 }
 
-fn resolve_use(
-    current_dir: &Path,
-    tree: &DependencyTree,
-    ast: ScopedAst,
+pub fn load(
+    file: &Path,
+    search_path: &[PathBuf],
 ) -> Result<ResolvedAst, PileError> {
-    let source = Rc::clone(&ast.as_ref().source);
-    let expressions: Result<Vec<Expr>, PileError> = ast
-        .ast()
-        .expressions
-        .into_iter()
-        .map(|expr| {
-            if let Expr::Use { subprogram, line } = expr {
-                let component_path = match &subprogram.source.as_ref() {
-                    ProgramSource::Repl | ProgramSource::Stdin => {
-                        panic!("applying 'use' to stdin or repl is impossible!")
-                    }
-                    ProgramSource::File(file) => file,
-                };
-
-                let component_path =
-                    normalize_path(&current_dir.join(&component_path))
-                        .map_err(|msg| {
-                            PileError::in_line(Rc::clone(&source), line, msg)
-                        })?;
-
-                if tree.contains(&component_path) {
-                    return Err(PileError::in_line(
-                        Rc::clone(&source),
-                        line,
-                        format!(
+    let source = Rc::new(ProgramSource::Repl);
+    let mut modules = ModuleCache::default();
+    let expressions =
+        load_module(file, Path::new("."), search_path, &mut modules)
+            .map_err(|msg| PileError::in_file(Rc::clone(&source), msg))?;
+
+    Ok(ResolvedAst(
+        Ast {
+            source,
+            expressions,
+        },
+        modules,
+    ))
+}
+
+// NOTE: Loads a module at runtime like a 'use' without an import list. The
+// modules in the cache keep the names they were imported with. The errors
+// don't have a location, since they belong to the code that asked for the
+// module.
+pub fn load_module(
+    file: &Path,
+    current_dir: &Path,
+    search_path: &[PathBuf],
+    modules: &mut ModuleCache,
+) -> Result<Vec<Expr>, String> {
+    let path = find_module(file, current_dir, search_path)?;
+    let source = Rc::new(ProgramSource::File(path.clone()));
+
+    let mut resolver = Resolver::new(search_path, current_dir);
+    resolver.modules = mem::take(&mut modules.0);
+    let used = resolver.use_module(
+        &path,
+        &DependencyTree::new(&PathBuf::new()),
+        &source,
+        0,
+        file.to_owned(),
+        Import::All,
+    );
+    let expressions = used.map(|(expr, aliases)| {
+        let mut expressions = vec![expr];
+        expressions.extend(forwards(&resolver.modules[&path], &aliases));
+        expressions
+    });
+    modules.0 = resolver.modules;
+
+    expressions.map_err(|err| err.to_string())
+}
+
+pub fn directory_of(path: &Path) -> PathBuf {
+    path.parent()
+        .map(&Path::to_owned)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+impl<'s> Resolver<'s> {
+    fn new(search_path: &'s [PathBuf], root: &Path) -> Self {
+        Resolver {
+            search_path,
+            root: root.canonicalize().unwrap_or_else(|_| root.to_owned()),
+            modules: HashMap::new(),
+        }
+    }
+
+    // NOTE: Modules are named relative to the directory of the program or the
+    // entry of the search path that contains them, so the names don't depend
+    // on where the project is stored.
+    fn namespace(&self, path: &Path) -> String {
+        let name = stdlib::name(path).unwrap_or_else(|| {
+            std::iter::once(self.root.clone())
+                .chain(
+                    self.search_path
+                        .iter()
+                        .filter_map(|dir| dir.canonicalize().ok()),
+                )
+                .find_map(|dir| path.strip_prefix(dir).ok().map(Path::to_owned))
+                .unwrap_or_else(|| path.to_owned())
+        });
+
+        namespace_of(&name)
+    }
+
+    fn resolve_use(
+        &mut self,
+        current_dir: &Path,
+        tree: &DependencyTree,
+        ast: ScopedAst,
+    ) -> Result<Ast, PileError> {
+        let source = Rc::clone(&ast.as_ref().source);
+        let mut aliases = HashMap::new();
+        let mut expressions = self.expressions(
+            current_dir,
+            tree,
+            &source,
+            ast.ast().expressions,
+            &mut aliases,
+        )?;
+        rename(&mut expressions, &aliases, &HashSet::new(), &HashSet::new());
+
+        Ok(Ast {
+            source,
+            expressions,
+        })
+    }
+
+    // NOTE: A 'use' inside a block is resolved with the rest of the file, so
+    // missing modules and cycles are reported before the program runs. The
+    // module itself only runs once the block is called. Like every other
+    // binding its definitions are global and aren't scoped by 'let'.
+    fn expressions(
+        &mut self,
+        current_dir: &Path,
+        tree: &DependencyTree,
+        source: &Rc<ProgramSource>,
+        expressions: Vec<Expr>,
+        aliases: &mut HashMap<String, String>,
+    ) -> Result<Vec<Expr>, PileError> {
+        let mut resolved = vec![];
+
+        for expr in expressions {
+            match expr {
+                Expr::Use {
+                    line, file, import, ..
+                } => {
+                    let error =
+                        |msg| PileError::in_line(Rc::clone(source), line, msg);
+
+                    let component_path =
+                        find_module(&file, current_dir, self.search_path)
+                            .map_err(error)?;
+
+                    if tree.contains(&component_path) {
+                        return Err(error(format!(
                             "Found cyclic use of '{}'.",
                             component_path.to_string_lossy()
-                        ),
-                    ));
+                        )));
+                    }
+
+                    let (expr, module_aliases) = self.use_module(
+                        &component_path,
+                        tree,
+                        source,
+                        line,
+                        file,
+                        import,
+                    )?;
+                    resolved.push(expr);
+                    aliases.extend(module_aliases);
+                }
+                Expr::Block {
+                    begin,
+                    end,
+                    locals,
+                    expressions,
+                } => {
+                    let expressions = Rc::try_unwrap(expressions)
+                        .unwrap_or_else(|expressions| (*expressions).clone());
+
+                    resolved.push(Expr::Block {
+                        begin,
+                        end,
+                        locals,
+                        expressions: Rc::new(self.expressions(
+                            current_dir,
+                            tree,
+                            source,
+                            expressions,
+                            aliases,
+                        )?),
+                    });
+                }
+                expr => resolved.push(expr),
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    // NOTE: Returns the use of the module and the names the import asks for
+    // that are bound to a different name by the first import.
+    fn use_module(
+        &mut self,
+        path: &PathBuf,
+        tree: &DependencyTree,
+        source: &Rc<ProgramSource>,
+        line: u64,
+        file: PathBuf,
+        import: Import,
+    ) -> Result<(Expr, Vec<(String, String)>), PileError> {
+        if !self.modules.contains_key(path) {
+            let module = self.module(path, tree, source, line, &import)?;
+            self.modules.insert(path.clone(), module);
+        }
+
+        let module = &self.modules[path];
+        let aliases = aliases(path, &self.namespace(path), module, &import)
+            .map_err(|msg| PileError::in_line(Rc::clone(source), line, msg))?;
+
+        let expr = Expr::Use {
+            line,
+            file,
+            subprogram: module.body.clone(),
+            import,
+        };
+
+        Ok((expr, aliases))
+    }
+
+    // NOTE: The names chosen by the first import of a module are the ones its
+    // body binds. Modules used for the first time by the body are renamed
+    // along with it, and their cached bodies and bindings are updated to match.
+    fn module(
+        &mut self,
+        path: &PathBuf,
+        tree: &DependencyTree,
+        source: &Rc<ProgramSource>,
+        line: u64,
+        import: &Import,
+    ) -> Result<Module, PileError> {
+        let known: HashSet<PathBuf> = self.modules.keys().cloned().collect();
+        let sub_ast = read_program(path, source, line)?;
+        let mut body = self.resolve_use(
+            &directory_of(path),
+            &tree.add_node(path),
+            sub_ast,
+        )?;
+        let nested: HashSet<PathBuf> = self
+            .modules
+            .keys()
+            .filter(|path| !known.contains(*path))
+            .cloned()
+            .collect();
+
+        let mut names = vec![];
+        definitions(&body.expressions, &nested, &mut names);
+
+        // NOTE: Reexported builtins can be imported like definitions, but
+        // aren't renamed in the body, which has to call the builtin.
+        let reexports = stdlib::reexports(path);
+        let exported: Vec<String> = names
+            .iter()
+            .cloned()
+            .chain(reexports.iter().map(|name| name.to_string()))
+            .collect();
+        let mut renames =
+            import_renames(import, &exported, path, &self.namespace(path))
+                .map_err(|msg| {
+                    PileError::in_line(Rc::clone(source), line, msg)
+                })?;
+        renames.retain(|name, _| !reexports.contains(&name.as_str()));
+        rename(&mut body.expressions, &renames, &HashSet::new(), &nested);
+
+        for nested_path in &nested {
+            let module = self
+                .modules
+                .get_mut(nested_path)
+                .expect("nested module isn't cached");
+
+            module.body = used_module(&body.expressions, nested_path)
+                .expect("nested module isn't used")
+                .clone();
+            for (_, bound) in module.bindings.iter_mut() {
+                if let Some(new_name) = renames.get(bound) {
+                    *bound = new_name.clone();
                 }
+            }
+        }
+
+        let bindings = names
+            .into_iter()
+            .map(|name| {
+                let bound = renames.get(&name).unwrap_or(&name).clone();
+                (name, bound)
+            })
+            .chain(
+                reexports
+                    .iter()
+                    .map(|name| (name.to_string(), name.to_string())),
+            )
+            .collect();
+
+        Ok(Module { body, bindings })
+    }
+}
+
+fn is_nested(subprogram: &Ast, nested: &HashSet<PathBuf>) -> bool {
+    match subprogram.source.as_ref() {
+        ProgramSource::File(path) => nested.contains(path),
+        _ => false,
+    }
+}
+
+fn used_module<'e>(expressions: &'e [Expr], path: &Path) -> Option<&'e Ast> {
+    expressions.iter().find_map(|expr| match expr {
+        Expr::Use { subprogram, .. }
+            if *subprogram.source == ProgramSource::File(path.to_owned()) =>
+        {
+            Some(subprogram)
+        }
+        Expr::Use { subprogram, .. } => {
+            used_module(&subprogram.expressions, path)
+        }
+        Expr::Block { expressions, .. } => used_module(expressions, path),
+        _ => None,
+    })
+}
 
-                let sub_ast = read_program(&component_path, &source, line)?;
-                let subprogram = resolve_use(
-                    &component_path
-                        .parent()
-                        .map(&Path::to_owned)
-                        .unwrap_or_else(|| PathBuf::from(".")),
-                    &tree.add_node(&component_path),
-                    sub_ast,
-                )?;
-
-                Ok(Expr::Use {
-                    subprogram: subprogram.0,
-                    line,
-                })
+// NOTE: The top-level definitions of a module include the ones of the modules
+// it uses for the first time. Modules that were used before keep their names.
+fn definitions(
+    expressions: &[Expr],
+    nested: &HashSet<PathBuf>,
+    names: &mut Vec<String>,
+) {
+    for expr in expressions {
+        match expr {
+            Expr::Assignment { var, .. } if !names.contains(var) => {
+                names.push(var.clone())
+            }
+            Expr::Use { subprogram, .. } if is_nested(subprogram, nested) => {
+                definitions(&subprogram.expressions, nested, names)
+            }
+            _ => (),
+        }
+    }
+}
+
+// NOTE: A module is only run by its first use. If a later import asks for
+// different names, they refer to the bindings of the first import. The
+// resolver renames them in the importing program, so every import shares the
+// same binding.
+fn aliases(
+    path: &Path,
+    namespace: &str,
+    module: &Module,
+    import: &Import,
+) -> Result<Vec<(String, String)>, String> {
+    let names: Vec<String> = module
+        .bindings
+        .iter()
+        .map(|(name, _)| name.clone())
+        .collect();
+    let renames = import_renames(import, &names, path, namespace)?;
+
+    Ok(module
+        .bindings
+        .iter()
+        .map(|(name, bound)| (renames.get(name).unwrap_or(name), bound))
+        .filter(|(wanted, bound)| wanted != bound)
+        .map(|(wanted, bound)| (wanted.clone(), bound.clone()))
+        .collect())
+}
+
+// NOTE: Code that loads a module at runtime is already resolved, so the
+// aliases are bound instead. Functions are wrapped in a block that forwards to
+// them, since the bare name would call the function.
+fn forwards(module: &Module, aliases: &[(String, String)]) -> Vec<Expr> {
+    let mut expressions = vec![];
+
+    for (wanted, bound) in aliases {
+        let binding = Expr::Atom {
+            line: 0,
+            token: Token::Identifier(bound.clone()),
+        };
+
+        if defines_function(&module.body.expressions, bound) {
+            expressions.push(Expr::Block {
+                begin: 0,
+                end: 0,
+                locals: vec![],
+                expressions: Rc::new(vec![binding]),
+            });
+        } else {
+            expressions.push(binding);
+        }
+        expressions.push(Expr::Assignment {
+            line: 0,
+            var: wanted.clone(),
+        });
+    }
+
+    expressions
+}
+
+// NOTE: A definition is a function if a block is assigned to it directly.
+fn defines_function(expressions: &[Expr], var: &str) -> bool {
+    expressions.windows(2).any(|pair| match pair {
+        [Expr::Block { .. }, Expr::Assignment { var: assigned, .. }] => {
+            assigned == var
+        }
+        _ => false,
+    }) || expressions.iter().any(|expr| match expr {
+        Expr::Use { subprogram, .. } => {
+            defines_function(&subprogram.expressions, var)
+        }
+        _ => false,
+    })
+}
+
+// NOTE: Every component of the path becomes a segment of the namespace, so
+// modules that share a file name don't clobber each other.
+fn namespace_of(path: &Path) -> String {
+    path.with_extension("")
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .map(|name| {
+            let segment: String = name
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+
+            if segment.starts_with(char::is_numeric) {
+                format!("_{}", segment)
             } else {
-                Ok(expr)
+                segment
             }
         })
-        .collect();
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+// NOTE: A namespaced import qualifies every top-level definition of the
+// module. A selective import keeps the selected names and moves the remaining
+// definitions into a namespace derived from the path of the module.
+fn import_renames(
+    import: &Import,
+    names: &[String],
+    module_path: &Path,
+    module_namespace: &str,
+) -> Result<HashMap<String, String>, String> {
+    let qualify = |namespace: &str, name: &str| {
+        (name.to_owned(), format!("{}.{}", namespace, name))
+    };
 
-    Ok(ResolvedAst(Ast {
-        source,
-        expressions: expressions?,
-    }))
+    match import {
+        Import::All => Ok(HashMap::new()),
+        Import::Namespace(namespace) => {
+            Ok(names.iter().map(|name| qualify(namespace, name)).collect())
+        }
+        Import::Names(selected) => {
+            if let Some(missing) =
+                selected.iter().find(|name| !names.contains(name))
+            {
+                return Err(format!(
+                    "'{}' isn't defined in '{}'.",
+                    missing,
+                    module_path.to_string_lossy()
+                ));
+            }
+
+            Ok(names
+                .iter()
+                .filter(|name| !selected.contains(name))
+                .map(|name| qualify(module_namespace, name))
+                .collect())
+        }
+    }
+}
+
+fn rename(
+    expressions: &mut [Expr],
+    renames: &HashMap<String, String>,
+    locals: &HashSet<String>,
+    nested: &HashSet<PathBuf>,
+) {
+    if renames.is_empty() {
+        return;
+    }
+
+    let renamed = |name: &mut String| {
+        if !locals.contains(name) {
+            if let Some(new_name) = renames.get(name) {
+                *name = new_name.clone();
+            }
+        }
+    };
+
+    for expr in expressions {
+        match expr {
+            Expr::Atom {
+                token: Token::Identifier(ident),
+                ..
+            } => renamed(ident),
+            Expr::Assignment { var, .. } => renamed(var),
+            Expr::Block {
+                locals: block_locals,
+                expressions,
+                ..
+            } => {
+                let mut locals = locals.clone();
+                locals.extend(block_locals.iter().cloned());
+                locals.extend(expressions.iter().filter_map(
+                    |expr| match expr {
+                        Expr::Save { var, .. } => Some(var.clone()),
+                        _ => None,
+                    },
+                ));
+
+                // NOTE: blocks of cached modules are shared with the cache
+                let expressions = Rc::make_mut(expressions);
+                rename(expressions, renames, &locals, nested);
+            }
+            Expr::Use { subprogram, .. } if is_nested(subprogram, nested) => {
+                rename(
+                    &mut subprogram.expressions,
+                    renames,
+                    &HashSet::new(),
+                    nested,
+                )
+            }
+            Expr::Atom { .. }
+            | Expr::Use { .. }
+            | Expr::Save { .. }
+            | Expr::Restore { .. } => (),
+        }
+    }
 }
 
-fn normalize_path(file: &PathBuf) -> Result<PathBuf, String> {
+fn with_extension(file: &Path) -> PathBuf {
     let mut file = file.to_owned();
-    if file.extension() == None {
+    if file.extension().is_none() {
         file.set_extension("pile");
     }
 
+    file
+}
+
+fn normalize_path(file: &Path) -> Result<PathBuf, String> {
+    let file = with_extension(file);
+
     let path = file
         .canonicalize()
         .map_err(|err| format!("{}: {}", file.to_string_lossy(), err))?;
@@ -165,18 +675,61 @@ fn normalize_path(file: &PathBuf) -> Result<PathBuf, String> {
     Ok(path)
 }
 
+// NOTE: The directory of the current file comes first, then the search path
+// in the given order.
+fn find_module(
+    file: &Path,
+    current_dir: &Path,
+    search_path: &[PathBuf],
+) -> Result<PathBuf, String> {
+    if let Some(module) = stdlib::find(file, current_dir) {
+        return module;
+    }
+
+    let mut candidates: Vec<PathBuf> = vec![];
+
+    for dir in std::iter::once(current_dir)
+        .chain(search_path.iter().map(|dir| dir.as_path()))
+    {
+        let candidate = with_extension(&dir.join(file));
+
+        if candidates.contains(&candidate) {
+            continue;
+        }
+
+        if let Ok(path) = candidate.canonicalize() {
+            return Ok(path);
+        }
+
+        candidates.push(candidate);
+    }
+
+    let mut message = format!(
+        "Couldn't find '{}'. Tried the following locations:",
+        file.to_string_lossy()
+    );
+    for candidate in candidates {
+        message += &format!("\n    {}", candidate.to_string_lossy());
+    }
+
+    Err(message)
+}
+
 fn read_program(
     file: &PathBuf,
     source: &Rc<ProgramSource>,
     line: u64,
 ) -> Result<ScopedAst, PileError> {
-    let program_text = fs::read_to_string(&file).map_err(|err| {
-        PileError::in_line(
-            Rc::clone(source),
-            line,
-            format!("{}: {}", file.to_string_lossy(), err),
-        )
-    })?;
+    let program_text = match stdlib::source(file) {
+        Some(program_text) => program_text.to_owned(),
+        None => fs::read_to_string(file).map_err(|err| {
+            PileError::in_line(
+                Rc::clone(source),
+                line,
+                format!("{}: {}", file.to_string_lossy(), err),
+            )
+        })?,
+    };
 
     let sub_source = Rc::new(ProgramSource::File(PathBuf::from(&file)));
     let lexer = Lexer::new(&program_text, Rc::clone(&sub_source));
@@ -1,17 +1,26 @@
 use crate::lex::*;
 use crate::parse::Ast;
 use crate::parse::Expr;
+use crate::parse::Import;
 use crate::parse::Parser;
 use crate::pile_error::PileError;
 use crate::program_source::ProgramSource;
-use crate::{locals, using};
+use crate::{locals, stdlib, using};
 use using::ResolvedAst;
 
 use std::fs;
+use std::path::Path;
 use std::path::PathBuf;
 use std::rc::Rc;
 
 fn resolve_file(s: &str) -> Result<ResolvedAst, PileError> {
+    resolve_file_with(s, &[])
+}
+
+fn resolve_file_with(
+    s: &str,
+    search_path: &[PathBuf],
+) -> Result<ResolvedAst, PileError> {
     let input = fs::read_to_string(s)
         .unwrap_or_else(|s| format!("couldn't read test file {}", s));
     let lexer =
@@ -20,7 +29,7 @@ fn resolve_file(s: &str) -> Result<ResolvedAst, PileError> {
 
     let ast = parser.parse().expect("couldn't parse test file");
     let ast = locals::translate(ast);
-    using::resolve(ast)
+    using::resolve(ast, search_path)
 }
 
 fn test_directory() -> String {
@@ -29,12 +38,12 @@ fn test_directory() -> String {
 
 fn assert_resolve_eq(s: &str, expr: Vec<Expr>) {
     let path = test_directory() + s;
-    let actual_ast = resolve_file(&path).expect("resolve error");
+    let actual_ast = resolve_file(&path).expect("resolve error").ast();
     let path = PathBuf::from(path);
-    let expected_ast = ResolvedAst(Ast {
+    let expected_ast = Ast {
         source: Rc::new(ProgramSource::File(path)),
         expressions: expr,
-    });
+    };
 
     if actual_ast != expected_ast {
         panic!(
@@ -57,6 +66,7 @@ fn test_simple_use() {
         "test_simple/simple.pile",
         vec![Expr::Use {
             line: 1,
+            file: PathBuf::from("other"),
             subprogram: Ast {
                 source: Rc::new(ProgramSource::File(PathBuf::from(
                     test_directory() + "test_simple/other.pile",
@@ -72,6 +82,7 @@ fn test_simple_use() {
                     },
                 ],
             },
+            import: Import::All,
         }],
     );
 }
@@ -83,6 +94,7 @@ fn test_tree() {
         vec![
             Expr::Use {
                 line: 8,
+                file: PathBuf::from("child1"),
                 subprogram: Ast {
                     source: Rc::new(ProgramSource::File(PathBuf::from(
                         test_directory() + "test_tree/child1.pile",
@@ -90,6 +102,7 @@ fn test_tree() {
                     expressions: vec![
                         Expr::Use {
                             line: 1,
+                            file: PathBuf::from("child1_1"),
                             subprogram: Ast {
                                 source: Rc::new(ProgramSource::File(
                                     PathBuf::from(
@@ -118,9 +131,11 @@ fn test_tree() {
                                     },
                                 ],
                             },
+                            import: Import::All,
                         },
                         Expr::Use {
                             line: 2,
+                            file: PathBuf::from("child1_2"),
                             subprogram: Ast {
                                 source: Rc::new(ProgramSource::File(
                                     PathBuf::from(
@@ -130,6 +145,7 @@ fn test_tree() {
                                 )),
                                 expressions: vec![],
                             },
+                            import: Import::All,
                         },
                         Expr::Atom {
                             line: 4,
@@ -141,15 +157,18 @@ fn test_tree() {
                         },
                     ],
                 },
+                import: Import::All,
             },
             Expr::Use {
                 line: 9,
+                file: PathBuf::from("child2.pile"),
                 subprogram: Ast {
                     source: Rc::new(ProgramSource::File(PathBuf::from(
                         test_directory() + "test_tree/child2.pile",
                     ))),
                     expressions: vec![],
                 },
+                import: Import::All,
             },
         ],
     );
@@ -190,6 +209,23 @@ fn test_cycle2() {
     )
 }
 
+#[test]
+fn test_cycle_nested() {
+    let relative_path = "test_cycle_nested/main.pile";
+    let absolute_path = test_directory() + relative_path;
+
+    assert_resolve_error(
+        relative_path,
+        PileError::in_line(
+            Rc::new(ProgramSource::File(PathBuf::from(
+                test_directory() + "test_cycle_nested/plugin.pile",
+            ))),
+            1,
+            format!("Found cyclic use of '{}'.", absolute_path),
+        ),
+    )
+}
+
 #[test]
 fn test_file_not_found() {
     let relative_path = "test_not_found/root.pile";
@@ -202,9 +238,115 @@ fn test_file_not_found() {
             ))),
             1,
             format!(
-                "{}: No such file or directory (os error 2)",
+                "Couldn't find 'unknown'. Tried the following locations:\n    \
+                 {}",
                 test_directory() + "test_not_found/unknown.pile"
             ),
         ),
     )
 }
+
+#[test]
+fn test_search_path() {
+    let path = test_directory() + "test_search_path/main.pile";
+    let search_path = vec![
+        PathBuf::from(test_directory() + "test_search_path/first"),
+        PathBuf::from(test_directory() + "test_search_path/second"),
+    ];
+
+    let ast = resolve_file_with(&path, &search_path).expect("resolve error");
+    let sources: Vec<_> = ast
+        .0
+        .expressions
+        .iter()
+        .filter_map(|expr| match expr {
+            Expr::Use { subprogram, .. } => Some(subprogram.source.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        sources,
+        vec![
+            "test_search_path/local.pile",
+            "test_search_path/first/lib.pile",
+            "test_search_path/second/extra.pile",
+        ]
+        .into_iter()
+        .map(|file| {
+            Rc::new(ProgramSource::File(PathBuf::from(test_directory() + file)))
+        })
+        .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_search_path_not_found() {
+    let path = test_directory() + "test_search_path/missing.pile";
+    let search_path = vec![
+        PathBuf::from(test_directory() + "test_search_path/first"),
+        PathBuf::from(test_directory() + "test_search_path/second"),
+    ];
+
+    assert_eq!(
+        resolve_file_with(&path, &search_path),
+        Err(PileError::in_line(
+            Rc::new(ProgramSource::File(PathBuf::from(path))),
+            1,
+            format!(
+                "Couldn't find 'nothing'. Tried the following locations:\
+                 \n    {0}test_search_path/nothing.pile\
+                 \n    {0}test_search_path/first/nothing.pile\
+                 \n    {0}test_search_path/second/nothing.pile",
+                test_directory()
+            ),
+        ))
+    );
+}
+
+#[test]
+fn test_namespace_of() {
+    assert_eq!(
+        using::namespace_of(Path::new("lib/My Lib/util.pile")),
+        "lib.my_lib.util"
+    );
+    assert_eq!(
+        using::namespace_of(Path::new("2021/util.pile")),
+        "_2021.util"
+    );
+    assert_eq!(
+        using::namespace_of(
+            &stdlib::name(Path::new("<std>/math.pile")).unwrap()
+        ),
+        "std.math"
+    );
+}
+
+#[test]
+fn test_alias_bindings() {
+    let path = test_directory() + "test_alias/main.pile";
+    let ast = resolve_file(&path).expect("resolve error");
+    let uses: Vec<Expr> = ast.0.expressions.into_iter().skip(2).collect();
+
+    assert_eq!(
+        uses,
+        vec![
+            Expr::Atom {
+                line: 4,
+                token: Token::Identifier("value".to_owned()),
+            },
+            Expr::Atom {
+                line: 5,
+                token: Token::Number(Number::Natural(1)),
+            },
+            Expr::Assignment {
+                line: 5,
+                var: "value".to_owned(),
+            },
+            Expr::Atom {
+                line: 6,
+                token: Token::Identifier("get".to_owned()),
+            },
+        ]
+    );
+}
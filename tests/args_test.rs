@@ -0,0 +1,23 @@
+use std::process::Command;
+
+fn run_args_test(env: Option<&str>) -> String {
+    let args_file = env!("CARGO_MANIFEST_DIR").to_owned() + "/tests/args.pile";
+    let mut command = Command::new(env!("CARGO_BIN_EXE_pile"));
+    command.args([&args_file, "--", "-v", "two words"]);
+
+    match env {
+        Some(value) => command.env("PILE_ARGS_TEST", value),
+        None => command.env_remove("PILE_ARGS_TEST"),
+    };
+
+    let output = command.output().expect("Couldn't run args test!");
+    assert!(output.status.success());
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn test_args() {
+    assert_eq!(run_args_test(Some("set")), "-v\ntwo words\nset\n");
+    assert_eq!(run_args_test(None), "-v\ntwo words\nunset\n");
+}
@@ -0,0 +1,46 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(program: &str, args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .args(args)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't run exit test!");
+
+    child
+        .stdin
+        .as_mut()
+        .expect("Failed to open stdin")
+        .write_all(program.as_bytes())
+        .expect("Failed to write to stdin");
+
+    child.wait_with_output().expect("Failed to wait for pile")
+}
+
+#[test]
+fn test_exit() {
+    let output = run("\"before\" print 3 exit \"after\" print", &[]);
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "before");
+}
+
+#[test]
+fn test_status_from_stack() {
+    assert_eq!(run("1 41 +", &[]).status.code(), Some(0));
+    assert_eq!(
+        run("1 41 +", &["--status-from-stack"]).status.code(),
+        Some(42)
+    );
+
+    let output = run("\"text\"", &["--status-from-stack"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Expected natural as exit status found string 'text'\n"
+    );
+}
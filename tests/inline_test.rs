@@ -0,0 +1,43 @@
+use std::process::Command;
+
+#[test]
+fn test_inline() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .args([
+            "-e",
+            "use \"tests/project/colors/palette\"",
+            "-e",
+            "primary upcase print",
+        ])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Couldn't run inline test!");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "RED\n");
+}
+
+#[test]
+fn test_inline_error() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .args(["-e", "1", "-e", "1 \"a\" +"])
+        .output()
+        .expect("Couldn't run inline test!");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "<inline>:2: Type error: natural '1', string 'a'\n"
+    );
+}
+
+#[test]
+fn test_inline_negative_number() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .args(["-e", "-1 print", "-e", "-2 print"])
+        .output()
+        .expect("Couldn't run inline test!");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "-1-2");
+}
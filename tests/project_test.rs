@@ -0,0 +1,83 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn project_dir(name: &str) -> String {
+    env!("CARGO_MANIFEST_DIR").to_owned() + "/tests/project/" + name
+}
+
+#[test]
+fn test_stdin_in_project() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .current_dir(project_dir("app/src"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Couldn't run project test!");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"\"piped\" println")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "piped\n");
+}
+
+#[test]
+fn test_project_tests() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .arg("--test")
+        .current_dir(project_dir("app"))
+        .output()
+        .expect("Couldn't run project test!");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "ok      {0}/greeting_test.pile\nok      {0}/shapes_test.pile\n\n\
+             2 passed, 0 failed\n",
+            project_dir("app/tests")
+        )
+    );
+}
+
+#[test]
+fn test_exiting_tests() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .arg("--test")
+        .current_dir(project_dir("exits"))
+        .output()
+        .expect("Couldn't run project test!");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "ok      {0}/a_test.pile\n\
+             FAILED  {0}/b_test.pile\n    {0}/b_test.pile:1: Exited with \
+             status 2\n\
+             ok      {0}/c_test.pile\n\n\
+             2 passed, 1 failed\n",
+            project_dir("exits/tests")
+        )
+    );
+}
+
+#[test]
+fn test_no_project() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .arg("--test")
+        .current_dir("/")
+        .output()
+        .expect("Couldn't run project test!");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Couldn't find 'pile.toml' for '--test'.\n"
+    );
+}
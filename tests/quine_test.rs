@@ -9,7 +9,7 @@ fn test_quine() {
     let pile_interpreter = env!("CARGO_BIN_EXE_pile");
 
     let output = Command::new(pile_interpreter)
-        .args(&[&quine_file])
+        .args([&quine_file])
         .output()
         .expect("Couldn't run quine test!");
 
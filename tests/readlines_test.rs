@@ -10,7 +10,7 @@ fn test_readlines() {
     let pile_interpreter = env!("CARGO_BIN_EXE_pile");
 
     let mut child = Command::new(pile_interpreter)
-        .args(&[&readlines_file])
+        .args([&readlines_file])
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .spawn()
@@ -30,3 +30,60 @@ fn test_readlines() {
         "=> HI AND BYE!\n=> QUIT\n"
     );
 }
+
+fn run_with_input(args: &[&str], input: &[u8]) -> (bool, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't run input test!");
+
+    child
+        .stdin
+        .take()
+        .expect("Failed to open stdin")
+        .write_all(input)
+        .expect("Failed to write to stdin");
+
+    let output = child.wait_with_output().expect("Failed to read output");
+
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn test_readlines_invalid_utf8() {
+    let program = "begin \"[{}]\" format print true end readlines";
+    let input = b"caf\xe9\r\nbar\r\n";
+
+    let (success, stdout, stderr) = run_with_input(&["-e", program], input);
+    assert!(!success);
+    assert_eq!(stdout, "");
+    assert_eq!(stderr, "<inline>:1: stdin: Invalid UTF-8 in input\n");
+
+    let (success, stdout, _) =
+        run_with_input(&["--lossy", "-e", program], input);
+    assert!(success);
+    assert_eq!(stdout, "[caf\u{fffd}][bar]");
+
+    let (success, stdout, _) =
+        run_with_input(&["--lossy", "--keep-cr", "-e", program], input);
+    assert!(success);
+    assert_eq!(stdout, "[caf\u{fffd}\r][bar\r]");
+}
+
+#[test]
+fn test_readline_and_readall() {
+    let program = "readline \"{} {}|\" format print readall print \
+                   readline \"{} [{}]\" format print";
+
+    let (success, stdout, _) =
+        run_with_input(&["-e", program], b"first\r\nrest\r\nof it");
+    assert!(success);
+    assert_eq!(stdout, "first true|rest\nof it [false]");
+}
@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run_repl(flags: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .arg("--repl")
+        .args(flags)
+        .env("HOME", std::env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Couldn't run repl test!");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+        + &String::from_utf8_lossy(&output.stderr)
+}
+
+#[test]
+fn test_repl_strict() {
+    assert_eq!(run_repl(&[], "1 2.0 + print\n"), "3\n");
+    assert_eq!(
+        run_repl(&["--strict"], "1 2.0 + print\n"),
+        "1: Numeric type mismatch: natural '1', float '2'\n"
+    );
+}
+
+#[test]
+fn test_repl_exit() {
+    let output = Command::new(env!("CARGO_BIN_EXE_pile"))
+        .arg("--repl")
+        .env("HOME", std::env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(b"\"a\" print 4 exit\n\"b\" print\n")?;
+            child.wait_with_output()
+        })
+        .expect("Couldn't run repl test!");
+
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a");
+}